@@ -0,0 +1,232 @@
+//! This module contains the detection of the terminal background (dark or light).
+
+use std::env;
+use std::time::Duration;
+
+use crate::query::{query_color, ColorQuery};
+use crate::support::is_stdout_tty;
+use crate::{Color, Palette};
+
+/// The time the terminal has to answer the background color query.
+const QUERY_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Represents the brightness of the terminal background.
+///
+/// # Examples
+///
+/// ```no_run
+/// use crossterm_style::{detect_background, Theme};
+///
+/// let theme = Theme::for_background(detect_background());
+///
+/// println!("{}", theme.apply("error", "file not found"));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Background {
+    /// The background is dark, the text should be light.
+    Dark,
+    /// The background is light, the text should be dark.
+    Light,
+    /// The background is not known.
+    Unknown,
+}
+
+impl Background {
+    /// Returns the background matching the color.
+    ///
+    /// The background is light if its relative luminance is closer to white than to black (the
+    /// black text has a better contrast than the white text). The named colors are turned into
+    /// RGB with the default [`Palette`](struct.Palette.html), `Color::Reset` is unknown.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossterm_style::{Background, Color};
+    ///
+    /// assert_eq!(Background::from_color(Color::Rgb { r: 40, g: 44, b: 52 }), Background::Dark);
+    /// assert_eq!(Background::from_color(Color::Rgb { r: 253, g: 246, b: 227 }), Background::Light);
+    /// ```
+    pub fn from_color(color: Color) -> Background {
+        match Palette::default().rgb(color) {
+            Some(rgb) if luminance(rgb) > CONTRAST_THRESHOLD => Background::Light,
+            Some(_) => Background::Dark,
+            None => Background::Unknown,
+        }
+    }
+
+    /// Detects the background from the given environment.
+    ///
+    /// The `var` function returns the value of the environment variable with the given name.
+    /// The following checks are made:
+    ///
+    /// * `COLORFGBG` (`fg;bg`, set by rxvt, Konsole, ...) gives the ANSI index of the
+    ///   background: `0` to `6` and `8` are dark, `7` and `9` to `15` are light.
+    /// * `TERM=linux` (the Linux console) is dark.
+    /// * Some terminals are recognized by the `TERM_PROGRAM` variable, their default profile
+    ///   is assumed (`Apple_Terminal` is light, `iTerm.app`, `WezTerm`, `Hyper` are dark).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossterm_style::Background;
+    ///
+    /// let background = Background::from_env(|key| match key {
+    ///     "COLORFGBG" => Some("0;15".to_string()),
+    ///     _ => None,
+    /// });
+    ///
+    /// assert_eq!(background, Background::Light);
+    /// ```
+    pub fn from_env<F>(var: F) -> Background
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        if let Some(colorfgbg) = var("COLORFGBG") {
+            let index = colorfgbg
+                .rsplit(';')
+                .next()
+                .and_then(|bg| bg.parse::<u8>().ok());
+
+            match index {
+                Some(0..=6) | Some(8) => return Background::Dark,
+                Some(7) | Some(9..=15) => return Background::Light,
+                _ => {}
+            }
+        }
+
+        if var("TERM").is_some_and(|term| term == "linux") {
+            return Background::Dark;
+        }
+
+        if let Some(program) = var("TERM_PROGRAM") {
+            match program.as_str() {
+                "Apple_Terminal" => return Background::Light,
+                "iTerm.app" | "WezTerm" | "Hyper" => return Background::Dark,
+                _ => {}
+            }
+        }
+
+        Background::Unknown
+    }
+
+    /// Returns `true` if the background is dark.
+    pub fn is_dark(self) -> bool {
+        self == Background::Dark
+    }
+
+    /// Returns `true` if the background is light.
+    pub fn is_light(self) -> bool {
+        self == Background::Light
+    }
+}
+
+/// Detects the background of the terminal.
+///
+/// The terminal is asked for its background color (see [`query_color`](fn.query_color.html))
+/// when the standard output is a terminal. The environment is checked if the terminal doesn't
+/// answer (see [`Background::from_env`](enum.Background.html#method.from_env)).
+///
+/// # Notes
+///
+/// The query reads the input of the terminal and can wait up to 100ms, the result should be
+/// detected once at the start of the application.
+pub fn detect_background() -> Background {
+    if is_stdout_tty() {
+        if let Ok(Some(color)) = query_color(ColorQuery::Background, QUERY_TIMEOUT) {
+            return Background::from_color(color);
+        }
+    }
+
+    Background::from_env(|key| env::var(key).ok())
+}
+
+/// The relative luminance at which the black and the white text have the same contrast.
+const CONTRAST_THRESHOLD: f64 = 0.179;
+
+/// Returns the relative luminance (WCAG 2.0) of the RGB color, from `0.0` to `1.0`.
+fn luminance((r, g, b): (u8, u8, u8)) -> f64 {
+    fn linear(channel: u8) -> f64 {
+        let value = f64::from(channel) / 255.0;
+
+        if value <= 0.03928 {
+            value / 12.92
+        } else {
+            ((value + 0.055) / 1.055).powf(2.4)
+        }
+    }
+
+    0.2126 * linear(r) + 0.7152 * linear(g) + 0.0722 * linear(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::{Background, Color};
+
+    use super::luminance;
+
+    fn detect(vars: &[(&str, &str)]) -> Background {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+
+        Background::from_env(|key| vars.get(key).cloned())
+    }
+
+    #[test]
+    fn test_luminance() {
+        assert_eq!(luminance((0, 0, 0)), 0.0);
+        assert!((luminance((255, 255, 255)) - 1.0).abs() < 1e-9);
+        assert!(luminance((0, 255, 0)) > luminance((255, 0, 0)));
+    }
+
+    #[test]
+    fn test_from_color() {
+        assert_eq!(Background::from_color(Color::Black), Background::Dark);
+        assert_eq!(Background::from_color(Color::White), Background::Light);
+        assert_eq!(
+            Background::from_color(Color::AnsiValue(17)),
+            Background::Dark
+        );
+        assert_eq!(
+            Background::from_color(Color::Rgb {
+                r: 238,
+                g: 232,
+                b: 213
+            }),
+            Background::Light
+        );
+        assert_eq!(Background::from_color(Color::Reset), Background::Unknown);
+    }
+
+    #[test]
+    fn test_colorfgbg() {
+        assert_eq!(detect(&[("COLORFGBG", "15;0")]), Background::Dark);
+        assert_eq!(detect(&[("COLORFGBG", "0;default;15")]), Background::Light);
+        assert_eq!(detect(&[("COLORFGBG", "0;8")]), Background::Dark);
+        assert_eq!(detect(&[("COLORFGBG", "0;7")]), Background::Light);
+        assert_eq!(detect(&[("COLORFGBG", "0;default")]), Background::Unknown);
+    }
+
+    #[test]
+    fn test_term_program() {
+        assert_eq!(
+            detect(&[("TERM_PROGRAM", "Apple_Terminal")]),
+            Background::Light
+        );
+        assert_eq!(detect(&[("TERM_PROGRAM", "iTerm.app")]), Background::Dark);
+        assert_eq!(detect(&[("TERM_PROGRAM", "vscode")]), Background::Unknown);
+        assert_eq!(
+            detect(&[("TERM_PROGRAM", "Apple_Terminal"), ("COLORFGBG", "15;0")]),
+            Background::Dark
+        );
+    }
+
+    #[test]
+    fn test_linux_console() {
+        assert_eq!(detect(&[("TERM", "linux")]), Background::Dark);
+        assert_eq!(detect(&[("TERM", "xterm")]), Background::Unknown);
+    }
+}
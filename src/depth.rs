@@ -0,0 +1,316 @@
+//! This module contains the color depth and the logic to downsample colors to it.
+
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use crate::Color;
+
+/// Represents the number of colors the output can show.
+///
+/// Colors which can't be shown with the given depth are replaced by the nearest color that can be
+/// (see [`Color::downsample`](enum.Color.html#method.downsample)).
+///
+/// # Examples
+///
+/// ```no_run
+/// use crossterm_style::{set_color_depth, style, Color, ColorDepth};
+///
+/// // Render all the colors as one of the 16 named ones.
+/// set_color_depth(ColorDepth::Ansi16);
+///
+/// println!("{}", style("Orange-ish").with(Color::Rgb { r: 255, g: 135, b: 0 }));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Ord, PartialOrd, Hash)]
+pub enum ColorDepth {
+    /// The 16 named colors (`Color::Black` ... `Color::White`).
+    Ansi16,
+    /// The 256 colors palette (`Color::AnsiValue`).
+    Ansi256,
+    /// 24-bit colors (`Color::Rgb`).
+    TrueColor,
+}
+
+/// The color depth used to render the `SetFg` & `SetBg` commands.
+static COLOR_DEPTH: AtomicU8 = AtomicU8::new(ColorDepth::TrueColor as u8);
+
+/// Sets the color depth used to render the colors.
+///
+/// All the colors written by the `SetFg`, `SetBg` commands (and everything built on top of them)
+/// are downsampled to this depth. The default value is `ColorDepth::TrueColor`, which leaves
+/// the colors untouched.
+pub fn set_color_depth(depth: ColorDepth) {
+    COLOR_DEPTH.store(depth as u8, Ordering::Relaxed);
+}
+
+/// Returns the color depth used to render the colors.
+///
+/// See [`set_color_depth`](fn.set_color_depth.html) for more info.
+pub fn color_depth() -> ColorDepth {
+    match COLOR_DEPTH.load(Ordering::Relaxed) {
+        0 => ColorDepth::Ansi16,
+        1 => ColorDepth::Ansi256,
+        _ => ColorDepth::TrueColor,
+    }
+}
+
+/// The 16 named colors ordered by their ANSI index.
+pub(crate) const NAMED_COLORS: [Color; 16] = [
+    Color::Black,
+    Color::DarkRed,
+    Color::DarkGreen,
+    Color::DarkYellow,
+    Color::DarkBlue,
+    Color::DarkMagenta,
+    Color::DarkCyan,
+    Color::Grey,
+    Color::DarkGrey,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::White,
+];
+
+/// The RGB values of the 16 named colors (default xterm palette), ordered by their ANSI index.
+pub(crate) const NAMED_COLORS_RGB: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+/// The channel values used by the 6x6x6 color cube (indexes 16 - 231).
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+impl Color {
+    /// Returns the nearest color that can be shown with the given depth.
+    ///
+    /// * `ColorDepth::TrueColor` leaves the color untouched.
+    /// * `ColorDepth::Ansi256` maps `Color::Rgb` to the nearest `Color::AnsiValue` from the
+    ///   6x6x6 color cube or the grayscale ramp.
+    /// * `ColorDepth::Ansi16` maps `Color::Rgb` & `Color::AnsiValue` to the nearest named color.
+    ///
+    /// The nearest color is found with a perceptually weighted RGB distance.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossterm_style::{Color, ColorDepth};
+    ///
+    /// let orange = Color::Rgb { r: 255, g: 135, b: 0 };
+    ///
+    /// assert_eq!(orange.downsample(ColorDepth::Ansi256), Color::AnsiValue(208));
+    /// assert_eq!(orange.downsample(ColorDepth::Ansi16), Color::DarkYellow);
+    /// ```
+    pub fn downsample(self, depth: ColorDepth) -> Color {
+        match (depth, self) {
+            (ColorDepth::Ansi256, Color::Rgb { r, g, b }) => {
+                Color::AnsiValue(nearest_ansi_256((r, g, b)))
+            }
+            (ColorDepth::Ansi16, Color::AnsiValue(val)) if val < 16 => NAMED_COLORS[val as usize],
+            (ColorDepth::Ansi16, Color::Rgb { .. }) | (ColorDepth::Ansi16, Color::AnsiValue(_)) => {
+                // safe unwrap, only `Color::Reset` has no RGB value
+                nearest_named(self.to_rgb().unwrap())
+            }
+            _ => self,
+        }
+    }
+
+    /// Returns the RGB value of the color.
+    ///
+    /// The named colors (and the first 16 ANSI values) are resolved with the default xterm
+    /// palette, the actual values depend on the terminal configuration, see
+    /// [`Palette::rgb`](struct.Palette.html#method.rgb) to use another palette. Returns `None`
+    /// for `Color::Reset`.
+    pub fn to_rgb(self) -> Option<(u8, u8, u8)> {
+        match self {
+            Color::Reset => None,
+            Color::Rgb { r, g, b } => Some((r, g, b)),
+            Color::AnsiValue(val) => Some(ansi_256_to_rgb(val)),
+            // safe unwrap, all the other colors are named ones
+            _ => Some(NAMED_COLORS_RGB[self.ansi_index().unwrap() as usize]),
+        }
+    }
+
+    /// Returns the ANSI index (0 - 15) of the named colors.
+    pub(crate) fn ansi_index(self) -> Option<u8> {
+        NAMED_COLORS
+            .iter()
+            .position(|color| *color == self)
+            .map(|index| index as u8)
+    }
+}
+
+/// Returns the RGB value of the given ANSI (256 colors palette) value.
+fn ansi_256_to_rgb(val: u8) -> (u8, u8, u8) {
+    match val {
+        0..=15 => NAMED_COLORS_RGB[val as usize],
+        16..=231 => {
+            let index = val - 16;
+            (
+                CUBE_LEVELS[(index / 36) as usize],
+                CUBE_LEVELS[(index / 6 % 6) as usize],
+                CUBE_LEVELS[(index % 6) as usize],
+            )
+        }
+        _ => {
+            let level = 8 + (val - 232) * 10;
+            (level, level, level)
+        }
+    }
+}
+
+/// Returns the index of the nearest channel value in the color cube.
+fn nearest_cube_index(val: u8) -> u8 {
+    match val {
+        0..=47 => 0,
+        48..=114 => 1,
+        _ => (val - 35) / 40,
+    }
+}
+
+/// Returns the ANSI value (16 - 255) nearest to the given RGB value.
+///
+/// The first 16 values are skipped, because they're redefined by most of the terminal themes.
+fn nearest_ansi_256(rgb: (u8, u8, u8)) -> u8 {
+    let (r, g, b) = rgb;
+
+    let cube = 16 + 36 * nearest_cube_index(r) + 6 * nearest_cube_index(g) + nearest_cube_index(b);
+
+    let average = (u16::from(r) + u16::from(g) + u16::from(b)) / 3;
+    let gray = 232 + (average.saturating_sub(3) / 10).min(23) as u8;
+
+    if distance(rgb, ansi_256_to_rgb(gray)) < distance(rgb, ansi_256_to_rgb(cube)) {
+        gray
+    } else {
+        cube
+    }
+}
+
+/// Returns the named color nearest to the given RGB value.
+fn nearest_named(rgb: (u8, u8, u8)) -> Color {
+    let index = NAMED_COLORS_RGB
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, named)| distance(rgb, **named))
+        .map(|(index, _)| index)
+        .unwrap_or(0);
+
+    NAMED_COLORS[index]
+}
+
+/// Returns the perceptual distance between two RGB values.
+///
+/// This is the squared "redmean" distance, a cheap approximation which weights the channels
+/// according to the human eye sensitivity.
+fn distance(lhs: (u8, u8, u8), rhs: (u8, u8, u8)) -> u32 {
+    let red_mean = (i32::from(lhs.0) + i32::from(rhs.0)) / 2;
+    let r = i32::from(lhs.0) - i32::from(rhs.0);
+    let g = i32::from(lhs.1) - i32::from(rhs.1);
+    let b = i32::from(lhs.2) - i32::from(rhs.2);
+
+    ((((512 + red_mean) * r * r) >> 8) + 4 * g * g + (((767 - red_mean) * b * b) >> 8)) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Color, ColorDepth};
+
+    #[test]
+    fn test_true_color_is_untouched() {
+        let color = Color::Rgb { r: 1, g: 2, b: 3 };
+        assert_eq!(color.downsample(ColorDepth::TrueColor), color);
+        assert_eq!(
+            Color::AnsiValue(100).downsample(ColorDepth::TrueColor),
+            Color::AnsiValue(100)
+        );
+    }
+
+    #[test]
+    fn test_downsample_rgb_to_cube() {
+        let rgb = Color::Rgb { r: 255, g: 0, b: 0 };
+        assert_eq!(rgb.downsample(ColorDepth::Ansi256), Color::AnsiValue(196));
+
+        let rgb = Color::Rgb {
+            r: 0,
+            g: 95,
+            b: 135,
+        };
+        assert_eq!(rgb.downsample(ColorDepth::Ansi256), Color::AnsiValue(24));
+    }
+
+    #[test]
+    fn test_downsample_rgb_to_grayscale() {
+        let rgb = Color::Rgb {
+            r: 128,
+            g: 128,
+            b: 128,
+        };
+        assert_eq!(rgb.downsample(ColorDepth::Ansi256), Color::AnsiValue(244));
+
+        let rgb = Color::Rgb {
+            r: 18,
+            g: 18,
+            b: 18,
+        };
+        assert_eq!(rgb.downsample(ColorDepth::Ansi256), Color::AnsiValue(233));
+    }
+
+    #[test]
+    fn test_downsample_to_named() {
+        let rgb = Color::Rgb {
+            r: 250,
+            g: 10,
+            b: 10,
+        };
+        assert_eq!(rgb.downsample(ColorDepth::Ansi16), Color::Red);
+
+        let rgb = Color::Rgb {
+            r: 10,
+            g: 10,
+            b: 10,
+        };
+        assert_eq!(rgb.downsample(ColorDepth::Ansi16), Color::Black);
+
+        assert_eq!(
+            Color::AnsiValue(4).downsample(ColorDepth::Ansi16),
+            Color::DarkBlue
+        );
+        assert_eq!(
+            Color::AnsiValue(231).downsample(ColorDepth::Ansi16),
+            Color::White
+        );
+    }
+
+    #[test]
+    fn test_named_colors_are_untouched() {
+        assert_eq!(
+            Color::DarkCyan.downsample(ColorDepth::Ansi16),
+            Color::DarkCyan
+        );
+        assert_eq!(Color::Reset.downsample(ColorDepth::Ansi16), Color::Reset);
+        assert_eq!(Color::Red.downsample(ColorDepth::Ansi256), Color::Red);
+    }
+
+    #[test]
+    fn test_ansi_value_to_rgb() {
+        assert_eq!(Color::AnsiValue(16).to_rgb(), Some((0, 0, 0)));
+        assert_eq!(Color::AnsiValue(231).to_rgb(), Some((255, 255, 255)));
+        assert_eq!(Color::AnsiValue(232).to_rgb(), Some((8, 8, 8)));
+        assert_eq!(Color::AnsiValue(255).to_rgb(), Some((238, 238, 238)));
+        assert_eq!(Color::Reset.to_rgb(), None);
+    }
+}
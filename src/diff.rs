@@ -0,0 +1,247 @@
+//! This module contains the logic to compute the shortest SGR sequence between two styles.
+
+use crossterm_utils::csi;
+
+use crate::style::ansi::{colored_sgr, style_params};
+use crate::{color_depth, Attribute, Attributes, Color, ColorDepth, Colored, ObjectStyle};
+
+/// The attributes turned off by the SGR parameters (`22`, `23`, ...), ordered by parameter.
+pub(crate) const OFF_GROUPS: [(Attribute, &[Attribute]); 9] = [
+    (
+        Attribute::NormalIntensity,
+        &[Attribute::Bold, Attribute::Dim],
+    ),
+    (
+        Attribute::NoItalic,
+        &[Attribute::Italic, Attribute::Fraktur],
+    ),
+    (Attribute::NoUnderline, &[Attribute::Underlined]),
+    (
+        Attribute::NoBlink,
+        &[Attribute::SlowBlink, Attribute::RapidBlink],
+    ),
+    (Attribute::NoInverse, &[Attribute::Reverse]),
+    (Attribute::NoHidden, &[Attribute::Hidden]),
+    (Attribute::NotCrossedOut, &[Attribute::CrossedOut]),
+    (
+        Attribute::NotFramedOrEncircled,
+        &[Attribute::Framed, Attribute::Encircled],
+    ),
+    (Attribute::NotOverLined, &[Attribute::OverLined]),
+];
+
+/// Returns the shortest SGR sequence changing the terminal style from `from` to `to`.
+///
+/// Both styles describe the whole terminal state: a missing color is the default one and only
+/// the attributes turning something on (`Bold`, `Italic`, ...) are taken into account. The
+/// sequence either turns off the attributes and colors with their specific parameters
+/// (`22`, `24`, `39`, ...) or resets everything (`0`), whichever is shorter, and combines all
+/// the parameters in a single `CSI ... m` sequence. An empty string is returned if the styles
+/// are the same.
+///
+/// The colors are downsampled to the global [`ColorDepth`](enum.ColorDepth.html).
+///
+/// # Examples
+///
+/// ```
+/// use crossterm_style::{sgr_diff, Attribute, Color, ObjectStyle};
+///
+/// let mut from = ObjectStyle::new().fg(Color::Red);
+/// from.add_attr(Attribute::Bold);
+/// from.add_attr(Attribute::Underlined);
+///
+/// let mut to = ObjectStyle::new().fg(Color::Red);
+/// to.add_attr(Attribute::Bold);
+///
+/// assert_eq!(sgr_diff(&from, &to), "\x1B[24m");
+/// assert_eq!(sgr_diff(&from, &ObjectStyle::new()), "\x1B[0m");
+/// assert_eq!(sgr_diff(&to, &to), "");
+/// ```
+pub fn sgr_diff(from: &ObjectStyle, to: &ObjectStyle) -> String {
+    let params = diff_params(from, to, color_depth());
+
+    if params.is_empty() {
+        String::new()
+    } else {
+        format!(csi!("{}m"), params.join(";"))
+    }
+}
+
+/// Returns the SGR parameters of the shortest transition between the styles.
+pub(crate) fn diff_params(from: &ObjectStyle, to: &ObjectStyle, depth: ColorDepth) -> Vec<String> {
+    let incremental = incremental_params(from, to, depth);
+
+    if incremental.is_empty() {
+        return incremental;
+    }
+
+    let mut reset = vec!["0".to_string()];
+    reset.extend(state_params(to, depth));
+
+    if reset.join(";").len() < incremental.join(";").len() {
+        reset
+    } else {
+        incremental
+    }
+}
+
+/// Returns the SGR parameters setting the style from the default terminal style.
+fn state_params(style: &ObjectStyle, depth: ColorDepth) -> Vec<String> {
+    style_params(
+        &ObjectStyle {
+            fg_color: color(style.fg_color),
+            bg_color: color(style.bg_color),
+            attrs: enabled(style.attrs),
+        },
+        depth,
+    )
+}
+
+/// Returns the SGR parameters changing the style without resetting it.
+fn incremental_params(from: &ObjectStyle, to: &ObjectStyle, depth: ColorDepth) -> Vec<String> {
+    let mut params = Vec::new();
+
+    let mut current = enabled(from.attrs);
+    let target = enabled(to.attrs);
+
+    for (off, group) in OFF_GROUPS.iter() {
+        if group
+            .iter()
+            .any(|attr| current.contains(*attr) && !target.contains(*attr))
+        {
+            params.push((*off as u8).to_string());
+            group.iter().for_each(|attr| current.remove(*attr));
+        }
+    }
+
+    params.extend(
+        target
+            .difference(current)
+            .iter()
+            .map(|attr| (attr as u8).to_string()),
+    );
+
+    let (from_fg, to_fg) = (color(from.fg_color), color(to.fg_color));
+    if from_fg != to_fg {
+        params.push(colored_sgr(
+            Colored::Fg(to_fg.unwrap_or(Color::Reset)),
+            depth,
+        ));
+    }
+
+    let (from_bg, to_bg) = (color(from.bg_color), color(to.bg_color));
+    if from_bg != to_bg {
+        params.push(colored_sgr(
+            Colored::Bg(to_bg.unwrap_or(Color::Reset)),
+            depth,
+        ));
+    }
+
+    params
+}
+
+/// Returns the attributes which turn something on.
+fn enabled(attrs: Attributes) -> Attributes {
+    attrs
+        .iter()
+        .filter(|attr| OFF_GROUPS.iter().any(|(_, group)| group.contains(attr)))
+        .collect()
+}
+
+/// Returns `None` for the default color.
+fn color(color: Option<Color>) -> Option<Color> {
+    color.filter(|color| *color != Color::Reset)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Attribute, Color, ColorDepth, ObjectStyle};
+
+    use super::diff_params;
+
+    fn diff(from: &ObjectStyle, to: &ObjectStyle) -> String {
+        diff_params(from, to, ColorDepth::TrueColor).join(";")
+    }
+
+    fn style(fg: Option<Color>, bg: Option<Color>, attrs: &[Attribute]) -> ObjectStyle {
+        ObjectStyle {
+            fg_color: fg,
+            bg_color: bg,
+            attrs: attrs.iter().cloned().collect(),
+        }
+    }
+
+    #[test]
+    fn test_same_styles() {
+        let style = style(Some(Color::Red), None, &[Attribute::Bold]);
+        assert_eq!(diff(&style, &style), "");
+
+        // the off attributes and default colors don't change the terminal state
+        assert_eq!(
+            diff(
+                &ObjectStyle::new(),
+                &self::style(Some(Color::Reset), None, &[Attribute::NoItalic])
+            ),
+            ""
+        );
+    }
+
+    #[test]
+    fn test_from_default_style() {
+        let to = style(
+            Some(Color::Red),
+            Some(Color::Blue),
+            &[Attribute::Bold, Attribute::Italic],
+        );
+
+        assert_eq!(diff(&ObjectStyle::new(), &to), "1;3;38;5;9;48;5;12");
+    }
+
+    #[test]
+    fn test_specific_off_parameters() {
+        let from = style(
+            Some(Color::Red),
+            Some(Color::Blue),
+            &[Attribute::Bold, Attribute::Underlined],
+        );
+        let to = style(None, Some(Color::Blue), &[Attribute::Bold]);
+
+        assert_eq!(diff(&from, &to), "24;39");
+    }
+
+    #[test]
+    fn test_shared_off_parameter() {
+        let from = style(Some(Color::Red), None, &[Attribute::Bold, Attribute::Dim]);
+        let to = style(
+            Some(Color::Red),
+            None,
+            &[Attribute::Dim, Attribute::Underlined],
+        );
+
+        assert_eq!(diff(&from, &to), "22;2;4");
+    }
+
+    #[test]
+    fn test_reset_when_shorter() {
+        let from = style(
+            Some(Color::Red),
+            Some(Color::Blue),
+            &[Attribute::Bold, Attribute::Underlined, Attribute::Reverse],
+        );
+
+        assert_eq!(diff(&from, &ObjectStyle::new()), "0");
+        assert_eq!(diff(&from, &style(None, None, &[Attribute::Italic])), "0;3");
+    }
+
+    #[test]
+    fn test_change_colors() {
+        let from = style(Some(Color::Red), Some(Color::Blue), &[]);
+        let to = style(
+            Some(Color::Rgb { r: 1, g: 2, b: 3 }),
+            Some(Color::Blue),
+            &[],
+        );
+
+        assert_eq!(diff(&from, &to), "38;2;1;2;3");
+    }
+}
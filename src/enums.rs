@@ -1,5 +1,23 @@
-pub use self::{attribute::Attribute, color::Color, colored::Colored};
+pub use self::{
+    attribute::Attribute,
+    attributes::{Attributes, AttributesIter},
+    color::Color,
+    colored::Colored,
+};
+pub(crate) use self::{
+    attribute::ATTRIBUTE_NAMES,
+    color::{format_color, COLOR_NAMES},
+};
 
 mod attribute;
+mod attributes;
 mod color;
 mod colored;
+
+/// Returns the lowercase name without the `_`, `-` and ` ` separators.
+pub(crate) fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| !matches!(c, '_' | '-' | ' '))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
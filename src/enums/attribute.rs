@@ -1,9 +1,9 @@
 use std::fmt::Display;
+use std::str::FromStr;
 
-#[cfg(feature = "serde")]
-use serde::{Deserialize, Serialize};
-
-use crate::SetAttr;
+use crate::enums::normalize_name;
+use crate::error::suggest;
+use crate::{ParseStyleError, ParseStyleErrorKind, SetAttr};
 
 /// Represents an attribute.
 ///
@@ -55,8 +55,8 @@ use crate::SetAttr;
 /// println!("{}", "Underlined text".underlined());
 /// println!("{}", "Negative text".negative());
 /// ```
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Ord, PartialOrd, Hash)]
+#[allow(clippy::manual_non_exhaustive)]
 pub enum Attribute {
     /// Resets all the attributes.
     Reset = 0,
@@ -119,3 +119,128 @@ impl Display for Attribute {
         Ok(())
     }
 }
+
+impl FromStr for Attribute {
+    type Err = ParseStyleError;
+
+    /// Creates an `Attribute` from the string representation.
+    ///
+    /// The names are the variant names with any case and separator (`crossed_out`,
+    /// `CrossedOut`, `crossed-out`). The common aliases (`underline`, `blink`, `inverse`,
+    /// `conceal`, `strikethrough`, `overline`) are accepted as well.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossterm_style::Attribute;
+    ///
+    /// assert_eq!("bold".parse(), Ok(Attribute::Bold));
+    /// assert_eq!("Crossed-Out".parse(), Ok(Attribute::CrossedOut));
+    /// assert_eq!("underline".parse(), Ok(Attribute::Underlined));
+    /// assert!("foo".parse::<Attribute>().is_err());
+    /// ```
+    fn from_str(src: &str) -> ::std::result::Result<Self, Self::Err> {
+        let position = src.len() - src.trim_start().len();
+        let src = src.trim();
+
+        if src.is_empty() {
+            return Err(ParseStyleError::new(src, ParseStyleErrorKind::Empty).at(position));
+        }
+
+        let name = normalize_name(src);
+
+        let attribute = match name.as_ref() {
+            "underline" => Some(Attribute::Underlined),
+            "blink" => Some(Attribute::SlowBlink),
+            "inverse" | "negative" => Some(Attribute::Reverse),
+            "conceal" => Some(Attribute::Hidden),
+            "strikethrough" | "strike" => Some(Attribute::CrossedOut),
+            "overline" => Some(Attribute::OverLined),
+            _ => ATTRIBUTE_NAMES
+                .iter()
+                .find(|(attribute_name, _)| normalize_name(attribute_name) == name)
+                .map(|(_, attribute)| *attribute),
+        };
+
+        attribute.ok_or_else(|| {
+            let suggestion = suggest(src, ATTRIBUTE_NAMES.iter().map(|(name, _)| *name));
+
+            ParseStyleError::new(src, ParseStyleErrorKind::UnknownAttribute)
+                .at(position)
+                .with_suggestion(suggestion)
+        })
+    }
+}
+
+impl Attribute {
+    /// Returns the attribute with the given SGR parameter.
+    pub(crate) fn from_sgr(code: u8) -> Option<Attribute> {
+        ATTRIBUTE_NAMES
+            .iter()
+            .find(|(_, attribute)| *attribute as u8 == code)
+            .map(|(_, attribute)| *attribute)
+    }
+
+    /// Returns the snake_case name of the attribute.
+    pub(crate) fn name(self) -> &'static str {
+        ATTRIBUTE_NAMES
+            .iter()
+            .find(|(_, attribute)| *attribute == self)
+            .map(|(name, _)| *name)
+            .unwrap_or_default()
+    }
+}
+
+/// The names of the attributes.
+pub(crate) const ATTRIBUTE_NAMES: [(&str, Attribute); 24] = [
+    ("reset", Attribute::Reset),
+    ("bold", Attribute::Bold),
+    ("dim", Attribute::Dim),
+    ("italic", Attribute::Italic),
+    ("underlined", Attribute::Underlined),
+    ("slow_blink", Attribute::SlowBlink),
+    ("rapid_blink", Attribute::RapidBlink),
+    ("reverse", Attribute::Reverse),
+    ("hidden", Attribute::Hidden),
+    ("crossed_out", Attribute::CrossedOut),
+    ("fraktur", Attribute::Fraktur),
+    ("no_bold", Attribute::NoBold),
+    ("normal_intensity", Attribute::NormalIntensity),
+    ("no_italic", Attribute::NoItalic),
+    ("no_underline", Attribute::NoUnderline),
+    ("no_blink", Attribute::NoBlink),
+    ("no_inverse", Attribute::NoInverse),
+    ("no_hidden", Attribute::NoHidden),
+    ("not_crossed_out", Attribute::NotCrossedOut),
+    ("framed", Attribute::Framed),
+    ("encircled", Attribute::Encircled),
+    ("over_lined", Attribute::OverLined),
+    ("not_framed_or_encircled", Attribute::NotFramedOrEncircled),
+    ("not_over_lined", Attribute::NotOverLined),
+];
+
+#[cfg(test)]
+mod tests {
+    use crate::{Attribute, ParseStyleErrorKind};
+
+    #[test]
+    fn test_known_attribute_conversion() {
+        assert_eq!("bold".parse(), Ok(Attribute::Bold));
+        assert_eq!("SlowBlink".parse(), Ok(Attribute::SlowBlink));
+        assert_eq!("not-crossed-out".parse(), Ok(Attribute::NotCrossedOut));
+        assert_eq!("no inverse".parse(), Ok(Attribute::NoInverse));
+        assert_eq!("strikethrough".parse(), Ok(Attribute::CrossedOut));
+    }
+
+    #[test]
+    fn test_unknown_attribute_conversion_fails() {
+        let error = " bolt".parse::<Attribute>().unwrap_err();
+
+        assert_eq!(error.kind(), ParseStyleErrorKind::UnknownAttribute);
+        assert_eq!(error.input(), "bolt");
+        assert_eq!(error.position(), 1);
+        assert_eq!(error.suggestion(), Some("bold"));
+
+        assert_eq!("xyz".parse::<Attribute>().unwrap_err().suggestion(), None);
+    }
+}
@@ -0,0 +1,344 @@
+use std::fmt::{self, Debug, Formatter};
+use std::iter::FromIterator;
+use std::ops::{BitAnd, BitOr, BitOrAssign, Sub};
+
+use crate::enums::ATTRIBUTE_NAMES;
+use crate::Attribute;
+
+/// Represents a set of attributes.
+///
+/// The set is a compact bitset, it's `Copy` and doesn't allocate. Each attribute is stored at
+/// most once and an attribute and the one turning it off are never stored together, inserting
+/// one removes the other (`Bold` and `NormalIntensity`, `Italic` and `NoItalic`, ...).
+///
+/// # Examples
+///
+/// ```
+/// use crossterm_style::{Attribute, Attributes};
+///
+/// let mut attributes = Attribute::Bold | Attribute::Underlined;
+/// assert!(attributes.contains(Attribute::Bold));
+///
+/// attributes.insert(Attribute::NormalIntensity);
+/// assert!(!attributes.contains(Attribute::Bold));
+///
+/// assert_eq!(
+///     attributes.iter().collect::<Vec<_>>(),
+///     vec![Attribute::Underlined, Attribute::NormalIntensity]
+/// );
+/// ```
+#[derive(Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Attributes(u32);
+
+/// The attributes turned off by the other ones (`on`, `off`).
+const SWITCHES: [(u32, u32); 11] = [
+    (
+        Attribute::Bold.bit() | Attribute::Dim.bit(),
+        Attribute::NormalIntensity.bit(),
+    ),
+    (Attribute::Bold.bit(), Attribute::NoBold.bit()),
+    (
+        Attribute::Italic.bit() | Attribute::Fraktur.bit(),
+        Attribute::NoItalic.bit(),
+    ),
+    (Attribute::Underlined.bit(), Attribute::NoUnderline.bit()),
+    (
+        Attribute::SlowBlink.bit() | Attribute::RapidBlink.bit(),
+        Attribute::NoBlink.bit(),
+    ),
+    (Attribute::Reverse.bit(), Attribute::NoInverse.bit()),
+    (Attribute::Hidden.bit(), Attribute::NoHidden.bit()),
+    (Attribute::CrossedOut.bit(), Attribute::NotCrossedOut.bit()),
+    (
+        Attribute::Framed.bit() | Attribute::Encircled.bit(),
+        Attribute::NotFramedOrEncircled.bit(),
+    ),
+    (Attribute::OverLined.bit(), Attribute::NotOverLined.bit()),
+    (Attribute::SlowBlink.bit(), Attribute::RapidBlink.bit()),
+];
+
+impl Attribute {
+    /// Returns the bit representing the attribute in the `Attributes` set.
+    pub(crate) const fn bit(self) -> u32 {
+        let index = match self as u8 {
+            code @ 0..=9 => code,
+            code @ 20..=25 => code - 10,
+            code @ 27..=29 => code - 11,
+            code @ 51..=55 => code - 32,
+            _ => return 0,
+        };
+
+        1 << index
+    }
+}
+
+impl Attributes {
+    /// Creates an empty set.
+    pub fn new() -> Attributes {
+        Attributes(0)
+    }
+
+    /// Returns `true` if the set contains no attributes.
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns the number of attributes in the set.
+    pub fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// Returns `true` if the set contains the attribute.
+    pub fn contains(self, attr: Attribute) -> bool {
+        attr.bit() != 0 && self.0 & attr.bit() != 0
+    }
+
+    /// Adds the attribute to the set.
+    ///
+    /// The attributes which are turned off by the given one (and the other way round) are
+    /// removed from the set.
+    pub fn insert(&mut self, attr: Attribute) {
+        let bit = attr.bit();
+
+        for (on, off) in SWITCHES.iter() {
+            if bit & on != 0 {
+                self.0 &= !off;
+            }
+            if bit & off != 0 {
+                self.0 &= !on;
+            }
+        }
+
+        self.0 |= bit;
+    }
+
+    /// Removes the attribute from the set.
+    pub fn remove(&mut self, attr: Attribute) {
+        self.0 &= !attr.bit();
+    }
+
+    /// Returns the set with the attribute added.
+    ///
+    /// See [`insert`](#method.insert) for more info.
+    pub fn with(mut self, attr: Attribute) -> Attributes {
+        self.insert(attr);
+        self
+    }
+
+    /// Returns the set with the attributes of both sets.
+    ///
+    /// The attributes of `other` win over the ones they turn off in `self`.
+    pub fn union(self, other: Attributes) -> Attributes {
+        other.iter().fold(self, Attributes::with)
+    }
+
+    /// Returns the set with the attributes contained in both sets.
+    pub fn intersection(self, other: Attributes) -> Attributes {
+        Attributes(self.0 & other.0)
+    }
+
+    /// Returns the set with the attributes of `self` which are not in `other`.
+    pub fn difference(self, other: Attributes) -> Attributes {
+        Attributes(self.0 & !other.0)
+    }
+
+    /// Returns an iterator over the attributes ordered by their SGR parameter.
+    pub fn iter(self) -> AttributesIter {
+        AttributesIter {
+            attributes: self,
+            index: 0,
+        }
+    }
+}
+
+impl Debug for Attributes {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl From<Attribute> for Attributes {
+    fn from(attr: Attribute) -> Self {
+        Attributes::new().with(attr)
+    }
+}
+
+impl FromIterator<Attribute> for Attributes {
+    fn from_iter<I: IntoIterator<Item = Attribute>>(iter: I) -> Self {
+        iter.into_iter().fold(Attributes::new(), Attributes::with)
+    }
+}
+
+impl Extend<Attribute> for Attributes {
+    fn extend<I: IntoIterator<Item = Attribute>>(&mut self, iter: I) {
+        for attr in iter {
+            self.insert(attr);
+        }
+    }
+}
+
+impl IntoIterator for Attributes {
+    type Item = Attribute;
+    type IntoIter = AttributesIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl BitOr for Attributes {
+    type Output = Attributes;
+
+    fn bitor(self, rhs: Attributes) -> Self::Output {
+        self.union(rhs)
+    }
+}
+
+impl BitOr<Attribute> for Attributes {
+    type Output = Attributes;
+
+    fn bitor(self, rhs: Attribute) -> Self::Output {
+        self.with(rhs)
+    }
+}
+
+impl BitOr for Attribute {
+    type Output = Attributes;
+
+    fn bitor(self, rhs: Attribute) -> Self::Output {
+        Attributes::from(self).with(rhs)
+    }
+}
+
+impl BitOrAssign<Attribute> for Attributes {
+    fn bitor_assign(&mut self, rhs: Attribute) {
+        self.insert(rhs);
+    }
+}
+
+impl BitAnd for Attributes {
+    type Output = Attributes;
+
+    fn bitand(self, rhs: Attributes) -> Self::Output {
+        self.intersection(rhs)
+    }
+}
+
+impl Sub for Attributes {
+    type Output = Attributes;
+
+    fn sub(self, rhs: Attributes) -> Self::Output {
+        self.difference(rhs)
+    }
+}
+
+/// An iterator over the attributes of an [`Attributes`](struct.Attributes.html) set.
+#[derive(Clone, Debug)]
+pub struct AttributesIter {
+    attributes: Attributes,
+    index: usize,
+}
+
+impl Iterator for AttributesIter {
+    type Item = Attribute;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.index < ATTRIBUTE_NAMES.len() {
+            let (_, attr) = ATTRIBUTE_NAMES[self.index];
+            self.index += 1;
+
+            if self.attributes.contains(attr) {
+                return Some(attr);
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Attribute, Attributes};
+
+    #[test]
+    fn test_insert_remove_contains() {
+        let mut attributes = Attributes::new();
+        assert!(attributes.is_empty());
+
+        attributes.insert(Attribute::Bold);
+        attributes.insert(Attribute::Bold);
+        attributes.insert(Attribute::NotOverLined);
+
+        assert_eq!(attributes.len(), 2);
+        assert!(attributes.contains(Attribute::Bold));
+        assert!(attributes.contains(Attribute::NotOverLined));
+        assert!(!attributes.contains(Attribute::Dim));
+
+        attributes.remove(Attribute::Bold);
+        assert_eq!(attributes, Attributes::from(Attribute::NotOverLined));
+    }
+
+    #[test]
+    fn test_on_off_pairs() {
+        let attributes = Attribute::Bold | Attribute::Dim | Attribute::Italic;
+
+        assert_eq!(
+            attributes | Attribute::NormalIntensity,
+            Attribute::Italic | Attribute::NormalIntensity
+        );
+        assert_eq!(
+            attributes | Attribute::NoBold,
+            Attribute::Dim | Attribute::Italic | Attribute::NoBold
+        );
+        assert_eq!(
+            Attributes::from(Attribute::NoItalic) | Attribute::Italic,
+            Attributes::from(Attribute::Italic)
+        );
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let lhs = Attribute::Bold | Attribute::Underlined;
+        let rhs = Attribute::Underlined | Attribute::Reverse;
+
+        assert_eq!(
+            lhs.union(rhs),
+            Attribute::Bold | Attribute::Underlined | Attribute::Reverse
+        );
+        assert_eq!(lhs & rhs, Attributes::from(Attribute::Underlined));
+        assert_eq!(lhs - rhs, Attributes::from(Attribute::Bold));
+        assert_eq!(
+            lhs.union(Attributes::from(Attribute::NoUnderline)),
+            Attribute::Bold | Attribute::NoUnderline
+        );
+    }
+
+    #[test]
+    fn test_iterate_in_sgr_order() {
+        let attributes: Attributes = vec![
+            Attribute::NotOverLined,
+            Attribute::Reset,
+            Attribute::NoInverse,
+            Attribute::Italic,
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(
+            attributes.into_iter().collect::<Vec<_>>(),
+            vec![
+                Attribute::Reset,
+                Attribute::Italic,
+                Attribute::NoInverse,
+                Attribute::NotOverLined
+            ]
+        );
+    }
+
+    #[test]
+    fn test_every_attribute_has_a_bit() {
+        for (_, attr) in crate::enums::ATTRIBUTE_NAMES.iter() {
+            assert_eq!(Attributes::from(*attr).len(), 1);
+        }
+    }
+}
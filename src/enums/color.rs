@@ -1,8 +1,9 @@
 use std::convert::AsRef;
 use std::str::FromStr;
 
-#[cfg(feature = "serde")]
-use serde::{Deserialize, Serialize};
+use crate::enums::normalize_name;
+use crate::error::suggest;
+use crate::{ParseStyleError, ParseStyleErrorKind};
 
 /// Represents a color.
 ///
@@ -24,7 +25,6 @@ use serde::{Deserialize, Serialize};
 /// Most UNIX terminals and Windows 10 consoles support additional colors.
 /// See [`Color::Rgb`](enum.Color.html#variant.Rgb) or [`Color::AnsiValue`](enum.Color.html#variant.AnsiValue) for
 /// more info.
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Ord, PartialOrd, Hash)]
 pub enum Color {
     /// Resets the terminal color.
@@ -92,41 +92,254 @@ pub enum Color {
 }
 
 impl FromStr for Color {
-    type Err = ();
+    type Err = ParseStyleError;
 
     /// Creates a `Color` from the string representation.
     ///
-    /// # Notes
+    /// The following formats are supported (case insensitive):
     ///
-    /// * Returns `Color::White` in case of an unknown color.
-    /// * Does not return `Err` and you can safely unwrap.
+    /// * The color names with any separator (`dark_red`, `DarkRed`, `dark-red`, `dark red`).
+    /// * `default` for `Color::Reset`.
+    /// * The `bright` prefix for the light variants (`bright black` is `Color::DarkGrey`,
+    ///   `bright red` is `Color::Red`, ...).
+    /// * `#rgb` and `#rrggbb` for the `Color::Rgb` colors.
+    /// * `rgb(r, g, b)` with values from `0` to `255`.
+    /// * `hsl(h, s%, l%)` with the hue in degrees.
+    /// * `ansi(n)` or a bare integer from `0` to `255` for the `Color::AnsiValue` colors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossterm_style::Color;
+    ///
+    /// assert_eq!("dark-red".parse(), Ok(Color::DarkRed));
+    /// assert_eq!("#ff8800".parse(), Ok(Color::Rgb { r: 255, g: 136, b: 0 }));
+    /// assert_eq!("hsl(120, 100%, 50%)".parse(), Ok(Color::Rgb { r: 0, g: 255, b: 0 }));
+    /// assert_eq!("208".parse(), Ok(Color::AnsiValue(208)));
+    /// assert!("foo".parse::<Color>().is_err());
+    /// ```
     fn from_str(src: &str) -> ::std::result::Result<Self, Self::Err> {
-        let src = src.to_lowercase();
-
-        match src.as_ref() {
-            "black" => Ok(Color::Black),
-            "dark_grey" => Ok(Color::DarkGrey),
-            "red" => Ok(Color::Red),
-            "dark_red" => Ok(Color::DarkRed),
-            "green" => Ok(Color::Green),
-            "dark_green" => Ok(Color::DarkGreen),
-            "yellow" => Ok(Color::Yellow),
-            "dark_yellow" => Ok(Color::DarkYellow),
-            "blue" => Ok(Color::Blue),
-            "dark_blue" => Ok(Color::DarkBlue),
-            "magenta" => Ok(Color::Magenta),
-            "dark_magenta" => Ok(Color::DarkMagenta),
-            "cyan" => Ok(Color::Cyan),
-            "dark_cyan" => Ok(Color::DarkCyan),
-            "white" => Ok(Color::White),
-            "grey" => Ok(Color::Grey),
-            _ => Ok(Color::White),
+        let position = src.len() - src.trim_start().len();
+        let src = src.trim();
+        let lowercase = src.to_lowercase();
+        let error = |kind| ParseStyleError::new(src, kind).at(position);
+
+        if lowercase.is_empty() {
+            return Err(error(ParseStyleErrorKind::Empty));
+        }
+
+        if let Some(digits) = lowercase.strip_prefix('#') {
+            return parse_hex(digits).ok_or_else(|| error(ParseStyleErrorKind::InvalidHex));
+        }
+
+        if let Some(args) = function_args(&lowercase, "rgb") {
+            return parse_rgb(&args).map_err(error);
+        }
+
+        if let Some(args) = function_args(&lowercase, "hsl") {
+            return parse_hsl(&args).map_err(error);
         }
+
+        if let Some(args) = function_args(&lowercase, "ansi") {
+            return match args.as_slice() {
+                [value] => parse_ansi_value(value),
+                _ => Err(ParseStyleErrorKind::InvalidArguments),
+            }
+            .map_err(error);
+        }
+
+        if lowercase.chars().all(|c| c.is_ascii_digit()) {
+            return parse_ansi_value(&lowercase).map_err(error);
+        }
+
+        let name = normalize_name(&lowercase);
+
+        let color = match name.as_ref() {
+            "default" => Some(Color::Reset),
+            "darkgray" | "brightblack" => Some(Color::DarkGrey),
+            "gray" => Some(Color::Grey),
+            "brightred" => Some(Color::Red),
+            "brightgreen" => Some(Color::Green),
+            "brightyellow" => Some(Color::Yellow),
+            "brightblue" => Some(Color::Blue),
+            "brightmagenta" => Some(Color::Magenta),
+            "brightcyan" => Some(Color::Cyan),
+            "brightwhite" => Some(Color::White),
+            _ => COLOR_NAMES
+                .iter()
+                .find(|(color_name, _)| normalize_name(color_name) == name)
+                .map(|(_, color)| *color),
+        };
+
+        color.ok_or_else(|| {
+            let suggestion = suggest(src, COLOR_NAMES.iter().map(|(name, _)| *name));
+            error(ParseStyleErrorKind::UnknownColor).with_suggestion(suggestion)
+        })
+    }
+}
+
+/// The names of the named colors.
+pub(crate) const COLOR_NAMES: [(&str, Color); 17] = [
+    ("reset", Color::Reset),
+    ("black", Color::Black),
+    ("dark_grey", Color::DarkGrey),
+    ("red", Color::Red),
+    ("dark_red", Color::DarkRed),
+    ("green", Color::Green),
+    ("dark_green", Color::DarkGreen),
+    ("yellow", Color::Yellow),
+    ("dark_yellow", Color::DarkYellow),
+    ("blue", Color::Blue),
+    ("dark_blue", Color::DarkBlue),
+    ("magenta", Color::Magenta),
+    ("dark_magenta", Color::DarkMagenta),
+    ("cyan", Color::Cyan),
+    ("dark_cyan", Color::DarkCyan),
+    ("white", Color::White),
+    ("grey", Color::Grey),
+];
+
+/// Returns the string representation of the color.
+///
+/// The named colors are written with their snake_case name, `Color::Reset` as `default`,
+/// `Color::Rgb` as `#rrggbb` and `Color::AnsiValue` as `ansi(n)`.
+pub(crate) fn format_color(color: Color) -> String {
+    match color {
+        Color::Reset => "default".to_string(),
+        Color::Rgb { r, g, b } => format!("#{:02x}{:02x}{:02x}", r, g, b),
+        Color::AnsiValue(value) => format!("ansi({})", value),
+        _ => COLOR_NAMES
+            .iter()
+            .find(|(_, named)| *named == color)
+            .map(|(name, _)| name.to_string())
+            .unwrap_or_default(),
     }
 }
 
+/// Returns the comma separated arguments of the `name(...)` function.
+fn function_args(src: &str, name: &str) -> Option<Vec<String>> {
+    let args = src.strip_prefix(name)?.trim_start();
+    let args = args.strip_prefix('(')?.strip_suffix(')')?;
+
+    Some(args.split(',').map(|arg| arg.trim().to_string()).collect())
+}
+
+/// Parses the `rgb` or `rrggbb` hex digits.
+fn parse_hex(digits: &str) -> Option<Color> {
+    if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let channel = |index: usize, len: usize| {
+        let value = u8::from_str_radix(&digits[index * len..(index + 1) * len], 16).ok()?;
+        Some(if len == 1 { value * 0x11 } else { value })
+    };
+
+    let len = match digits.len() {
+        3 => 1,
+        6 => 2,
+        _ => return None,
+    };
+
+    Some(Color::Rgb {
+        r: channel(0, len)?,
+        g: channel(1, len)?,
+        b: channel(2, len)?,
+    })
+}
+
+/// Parses the `r, g, b` arguments.
+fn parse_rgb(args: &[String]) -> Result<Color, ParseStyleErrorKind> {
+    let channels = args
+        .iter()
+        .map(|arg| {
+            parse_number(arg)?
+                .parse::<u8>()
+                .map_err(|_| ParseStyleErrorKind::OutOfRange)
+        })
+        .collect::<Result<Vec<u8>, ParseStyleErrorKind>>()?;
+
+    match channels.as_slice() {
+        [r, g, b] => Ok(Color::Rgb {
+            r: *r,
+            g: *g,
+            b: *b,
+        }),
+        _ => Err(ParseStyleErrorKind::InvalidArguments),
+    }
+}
+
+/// Parses the `h, s%, l%` arguments.
+fn parse_hsl(args: &[String]) -> Result<Color, ParseStyleErrorKind> {
+    let (hue, saturation, lightness) = match args {
+        [h, s, l] => (
+            parse_float(h.strip_suffix("deg").unwrap_or(h))?,
+            parse_float(s.strip_suffix('%').unwrap_or(s))?,
+            parse_float(l.strip_suffix('%').unwrap_or(l))?,
+        ),
+        _ => return Err(ParseStyleErrorKind::InvalidArguments),
+    };
+
+    if !(0.0..=100.0).contains(&saturation) || !(0.0..=100.0).contains(&lightness) {
+        return Err(ParseStyleErrorKind::OutOfRange);
+    }
+
+    let (r, g, b) = hsl_to_rgb(hue.rem_euclid(360.0), saturation / 100.0, lightness / 100.0);
+
+    Ok(Color::Rgb { r, g, b })
+}
+
+/// Parses an `AnsiValue` color (`0` - `255`).
+fn parse_ansi_value(value: &str) -> Result<Color, ParseStyleErrorKind> {
+    parse_number(value)?
+        .parse::<u8>()
+        .map(Color::AnsiValue)
+        .map_err(|_| ParseStyleErrorKind::OutOfRange)
+}
+
+/// Checks that the value is a non-empty sequence of digits.
+fn parse_number(value: &str) -> Result<&str, ParseStyleErrorKind> {
+    if value.is_empty() || !value.chars().all(|c| c.is_ascii_digit()) {
+        return Err(ParseStyleErrorKind::InvalidArguments);
+    }
+
+    Ok(value)
+}
+
+fn parse_float(value: &str) -> Result<f64, ParseStyleErrorKind> {
+    value
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|value| value.is_finite())
+        .ok_or(ParseStyleErrorKind::InvalidArguments)
+}
+
+/// Converts the hue (`0` - `360`), saturation (`0` - `1`) and lightness (`0` - `1`) to RGB.
+fn hsl_to_rgb(hue: f64, saturation: f64, lightness: f64) -> (u8, u8, u8) {
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let sector = hue / 60.0;
+    let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+
+    let (r, g, b) = match sector as u8 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+
+    let m = lightness - chroma / 2.0;
+    let channel = |value: f64| ((value + m) * 255.0).round() as u8;
+
+    (channel(r), channel(g), channel(b))
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::ParseStyleErrorKind;
+
     use super::Color;
 
     #[test]
@@ -150,7 +363,140 @@ mod tests {
     }
 
     #[test]
-    fn test_unknown_color_conversion_yields_white() {
-        assert_eq!("foo".parse(), Ok(Color::White));
+    fn test_color_name_variants() {
+        assert_eq!("DarkRed".parse(), Ok(Color::DarkRed));
+        assert_eq!("dark-red".parse(), Ok(Color::DarkRed));
+        assert_eq!("Dark Red".parse(), Ok(Color::DarkRed));
+        assert_eq!("bright red".parse(), Ok(Color::Red));
+        assert_eq!("bright_black".parse(), Ok(Color::DarkGrey));
+        assert_eq!("gray".parse(), Ok(Color::Grey));
+        assert_eq!(" reset ".parse(), Ok(Color::Reset));
+        assert_eq!("default".parse(), Ok(Color::Reset));
+    }
+
+    #[test]
+    fn test_hex_color_conversion() {
+        assert_eq!(
+            "#ff8800".parse(),
+            Ok(Color::Rgb {
+                r: 255,
+                g: 136,
+                b: 0
+            })
+        );
+        assert_eq!(
+            "#FF8800".parse(),
+            Ok(Color::Rgb {
+                r: 255,
+                g: 136,
+                b: 0
+            })
+        );
+        assert_eq!(
+            "#f80".parse(),
+            Ok(Color::Rgb {
+                r: 255,
+                g: 136,
+                b: 0
+            })
+        );
+        assert_eq!(
+            "#f8".parse::<Color>().unwrap_err().kind(),
+            ParseStyleErrorKind::InvalidHex
+        );
+        assert_eq!(
+            "#ggg".parse::<Color>().unwrap_err().kind(),
+            ParseStyleErrorKind::InvalidHex
+        );
+    }
+
+    #[test]
+    fn test_rgb_color_conversion() {
+        assert_eq!("rgb(1, 2, 3)".parse(), Ok(Color::Rgb { r: 1, g: 2, b: 3 }));
+        assert_eq!(
+            "RGB(255,0,10)".parse(),
+            Ok(Color::Rgb {
+                r: 255,
+                g: 0,
+                b: 10
+            })
+        );
+        assert_eq!(
+            "rgb(256, 0, 0)".parse::<Color>().unwrap_err().kind(),
+            ParseStyleErrorKind::OutOfRange
+        );
+        assert_eq!(
+            "rgb(1, 2)".parse::<Color>().unwrap_err().kind(),
+            ParseStyleErrorKind::InvalidArguments
+        );
+    }
+
+    #[test]
+    fn test_hsl_color_conversion() {
+        assert_eq!(
+            "hsl(0, 100%, 50%)".parse(),
+            Ok(Color::Rgb { r: 255, g: 0, b: 0 })
+        );
+        assert_eq!(
+            "hsl(240, 100%, 50%)".parse(),
+            Ok(Color::Rgb { r: 0, g: 0, b: 255 })
+        );
+        assert_eq!(
+            "hsl(30, 100%, 50%)".parse(),
+            Ok(Color::Rgb {
+                r: 255,
+                g: 128,
+                b: 0
+            })
+        );
+        assert_eq!(
+            "hsl(0, 0%, 100%)".parse(),
+            Ok(Color::Rgb {
+                r: 255,
+                g: 255,
+                b: 255
+            })
+        );
+        assert_eq!(
+            "hsl(0, 120%, 50%)".parse::<Color>().unwrap_err().kind(),
+            ParseStyleErrorKind::OutOfRange
+        );
+    }
+
+    #[test]
+    fn test_ansi_color_conversion() {
+        assert_eq!("ansi(208)".parse(), Ok(Color::AnsiValue(208)));
+        assert_eq!("0".parse(), Ok(Color::AnsiValue(0)));
+        assert_eq!("255".parse(), Ok(Color::AnsiValue(255)));
+        assert_eq!(
+            "256".parse::<Color>().unwrap_err().kind(),
+            ParseStyleErrorKind::OutOfRange
+        );
+    }
+
+    #[test]
+    fn test_unknown_color_conversion_fails() {
+        let error = "foo".parse::<Color>().unwrap_err();
+
+        assert_eq!(error.kind(), ParseStyleErrorKind::UnknownColor);
+        assert_eq!(error.input(), "foo");
+        assert_eq!(error.suggestion(), None);
+        assert_eq!(
+            "".parse::<Color>().unwrap_err().kind(),
+            ParseStyleErrorKind::Empty
+        );
+    }
+
+    #[test]
+    fn test_unknown_color_suggestion() {
+        let error = "  dark_bleu".parse::<Color>().unwrap_err();
+
+        assert_eq!(error.input(), "dark_bleu");
+        assert_eq!(error.position(), 2);
+        assert_eq!(error.suggestion(), Some("dark_blue"));
+        assert_eq!(
+            error.to_string(),
+            "unknown color `dark_bleu` at position 2, did you mean `dark_blue`?"
+        );
     }
 }
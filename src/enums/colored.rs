@@ -3,8 +3,8 @@ use std::fmt::Display;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use crate::color;
 use crate::enums::Color;
+use crate::{color, should_colorize};
 
 /// Represents a foreground or a background color.
 ///
@@ -31,6 +31,10 @@ pub enum Colored {
 
 impl Display for Colored {
     fn fmt(&self, _f: &mut ::std::fmt::Formatter) -> std::result::Result<(), std::fmt::Error> {
+        if !should_colorize() {
+            return Ok(());
+        }
+
         let colored_terminal = color();
 
         match *self {
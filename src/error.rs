@@ -0,0 +1,206 @@
+//! This module contains the error returned when parsing a style fails.
+
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+use crate::enums::normalize_name;
+
+/// Represents the reason why parsing a style failed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[allow(clippy::manual_non_exhaustive)]
+pub enum ParseStyleErrorKind {
+    /// The input is empty.
+    Empty,
+    /// The input is not a known color.
+    UnknownColor,
+    /// The input is not a known attribute.
+    UnknownAttribute,
+    /// The input is neither a known color nor a known attribute.
+    UnknownName,
+    /// A color is missing after the `on` keyword.
+    MissingColor,
+    /// The input is not a valid `#rgb` or `#rrggbb` color.
+    InvalidHex,
+    /// The arguments of a `rgb(r, g, b)`, `hsl(h, s%, l%)` or `ansi(n)` color are invalid.
+    InvalidArguments,
+    /// A numeric value is out of the allowed range.
+    OutOfRange,
+    /// The style has more colors than a foreground and a background color.
+    TooManyColors,
+
+    #[doc(hidden)]
+    __Nonexhaustive,
+}
+
+/// An error which can be returned when parsing a style.
+///
+/// The error contains the offending part of the input, its position (byte offset) in the
+/// input and a suggestion for the misspelled color and attribute names.
+///
+/// # Examples
+///
+/// ```
+/// use crossterm_style::{Attribute, ParseStyleErrorKind};
+///
+/// let error = "undrelined".parse::<Attribute>().unwrap_err();
+///
+/// assert_eq!(error.kind(), ParseStyleErrorKind::UnknownAttribute);
+/// assert_eq!(error.input(), "undrelined");
+/// assert_eq!(error.suggestion(), Some("underlined"));
+/// assert_eq!(
+///     error.to_string(),
+///     "unknown attribute `undrelined` at position 0, did you mean `underlined`?"
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseStyleError {
+    input: String,
+    position: usize,
+    kind: ParseStyleErrorKind,
+    suggestion: Option<String>,
+}
+
+impl ParseStyleError {
+    pub(crate) fn new(input: &str, kind: ParseStyleErrorKind) -> ParseStyleError {
+        ParseStyleError {
+            input: input.to_string(),
+            position: 0,
+            kind,
+            suggestion: None,
+        }
+    }
+
+    /// Sets the position of the offending input.
+    pub(crate) fn at(mut self, position: usize) -> ParseStyleError {
+        self.position = position;
+        self
+    }
+
+    /// Moves the position of the offending input by the given offset.
+    pub(crate) fn offset(mut self, offset: usize) -> ParseStyleError {
+        self.position += offset;
+        self
+    }
+
+    pub(crate) fn with_suggestion(mut self, suggestion: Option<&str>) -> ParseStyleError {
+        self.suggestion = suggestion.map(str::to_string);
+        self
+    }
+
+    /// Returns the offending part of the input.
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    /// Returns the position (byte offset) of the offending part of the input.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns the reason why parsing failed.
+    pub fn kind(&self) -> ParseStyleErrorKind {
+        self.kind
+    }
+
+    /// Returns the name the offending part of the input is most likely a misspelling of.
+    pub fn suggestion(&self) -> Option<&str> {
+        self.suggestion.as_deref()
+    }
+}
+
+impl Display for ParseStyleError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self.kind {
+            ParseStyleErrorKind::Empty => return write!(f, "empty style"),
+            ParseStyleErrorKind::UnknownColor => write!(f, "unknown color `{}`", self.input)?,
+            ParseStyleErrorKind::UnknownAttribute => {
+                write!(f, "unknown attribute `{}`", self.input)?
+            }
+            ParseStyleErrorKind::UnknownName => {
+                write!(f, "unknown color or attribute `{}`", self.input)?
+            }
+            ParseStyleErrorKind::MissingColor => write!(f, "missing color after `{}`", self.input)?,
+            ParseStyleErrorKind::InvalidHex => write!(f, "invalid hex color `{}`", self.input)?,
+            ParseStyleErrorKind::InvalidArguments => {
+                write!(f, "invalid color arguments in `{}`", self.input)?
+            }
+            ParseStyleErrorKind::OutOfRange => write!(f, "value out of range in `{}`", self.input)?,
+            ParseStyleErrorKind::TooManyColors => write!(f, "too many colors at `{}`", self.input)?,
+            ParseStyleErrorKind::__Nonexhaustive => write!(f, "invalid style `{}`", self.input)?,
+        }
+
+        write!(f, " at position {}", self.position)?;
+
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, ", did you mean `{}`?", suggestion)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Error for ParseStyleError {}
+
+/// Returns the candidate which is the closest to the input.
+///
+/// The names are compared without the case and separators. Nothing is returned if the
+/// closest candidate is too far away (more than one third of the characters have to change).
+pub(crate) fn suggest<'a, I>(input: &str, candidates: I) -> Option<&'a str>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let input = normalize_name(input);
+    let max_distance = (input.chars().count() / 3).max(1);
+
+    candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(&input, &normalize_name(candidate)), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Returns the Levenshtein distance between two strings.
+fn edit_distance(lhs: &str, rhs: &str) -> usize {
+    let rhs: Vec<char> = rhs.chars().collect();
+    let mut previous: Vec<usize> = (0..=rhs.len()).collect();
+    let mut current = vec![0; rhs.len() + 1];
+
+    for (i, lhs_char) in lhs.chars().enumerate() {
+        current[0] = i + 1;
+
+        for (j, rhs_char) in rhs.iter().enumerate() {
+            let substitution = previous[j] + if lhs_char == *rhs_char { 0 } else { 1 };
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[rhs.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{edit_distance, suggest};
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("", "red"), 3);
+        assert_eq!(edit_distance("red", "red"), 0);
+        assert_eq!(edit_distance("rde", "red"), 2);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn test_suggest() {
+        let candidates = ["red", "dark_red", "green"];
+
+        assert_eq!(suggest("redd", candidates.iter().cloned()), Some("red"));
+        assert_eq!(
+            suggest("Dark-Rde", candidates.iter().cloned()),
+            Some("dark_red")
+        );
+        assert_eq!(suggest("blue", candidates.iter().cloned()), None);
+    }
+}
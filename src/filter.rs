@@ -0,0 +1,185 @@
+//! This module contains a filter which removes the escape sequences from a byte stream.
+
+use crate::parser::{split_incomplete, Token, Tokens};
+
+/// The escape sequences removed by the filter.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Mode {
+    /// Only the SGR sequences.
+    Sgr,
+    /// All the escape sequences.
+    All,
+}
+
+/// Removes the escape sequences from a byte stream.
+///
+/// The sequences are read with the same tokenizer as `strip_ansi` and `parse_ansi`. The input
+/// can be split at any position, incomplete sequences and UTF-8 characters are buffered until
+/// the next call. Malformed sequences are removed up to the unexpected character, invalid
+/// UTF-8 is written as is.
+#[derive(Debug)]
+pub(crate) struct EscapeFilter {
+    mode: Mode,
+    pending: Vec<u8>,
+}
+
+impl EscapeFilter {
+    /// Creates a filter removing the SGR (`CSI ... m`) sequences only, all the other escape
+    /// sequences are left untouched.
+    pub(crate) fn sgr() -> EscapeFilter {
+        EscapeFilter::new(Mode::Sgr)
+    }
+
+    /// Creates a filter removing all the escape sequences.
+    pub(crate) fn all() -> EscapeFilter {
+        EscapeFilter::new(Mode::All)
+    }
+
+    fn new(mode: Mode) -> EscapeFilter {
+        EscapeFilter {
+            mode,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Filters the given input and appends the result to `output`.
+    pub(crate) fn filter(&mut self, input: &[u8], output: &mut Vec<u8>) {
+        self.pending.extend_from_slice(input);
+
+        let complete = complete_utf8_len(&self.pending);
+        let mut held_back = Vec::new();
+        let mut chunks = self.pending[..complete].utf8_chunks().peekable();
+
+        while let Some(chunk) = chunks.next() {
+            let mut text = chunk.valid();
+
+            if chunk.invalid().is_empty() && chunks.peek().is_none() {
+                let (complete, incomplete) = split_incomplete(text);
+                text = complete;
+                held_back.extend_from_slice(incomplete.as_bytes());
+            }
+
+            for token in Tokens::new(text) {
+                match (self.mode, token) {
+                    (_, Token::Text(text)) | (Mode::Sgr, Token::Escape(text)) => {
+                        output.extend_from_slice(text.as_bytes())
+                    }
+                    _ => {}
+                }
+            }
+
+            output.extend_from_slice(chunk.invalid());
+        }
+
+        held_back.extend_from_slice(&self.pending[complete..]);
+        self.pending = held_back;
+    }
+}
+
+/// Returns the length of the input without the UTF-8 character cut at the end, if any.
+fn complete_utf8_len(input: &[u8]) -> usize {
+    let mut offset = 0;
+
+    loop {
+        match std::str::from_utf8(&input[offset..]) {
+            Ok(_) => return input.len(),
+            Err(error) => match error.error_len() {
+                Some(len) => offset += error.valid_up_to() + len,
+                None => return offset + error.valid_up_to(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EscapeFilter;
+
+    fn filter(mut filter: EscapeFilter, chunks: &[&str]) -> String {
+        let mut output = Vec::new();
+
+        for chunk in chunks {
+            filter.filter(chunk.as_bytes(), &mut output);
+        }
+
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_filter_sgr_sequences() {
+        assert_eq!(
+            filter(EscapeFilter::sgr(), &["\x1B[38;5;9mred\x1B[0m text"]),
+            "red text"
+        );
+    }
+
+    #[test]
+    fn test_filter_split_sequence() {
+        assert_eq!(
+            filter(
+                EscapeFilter::sgr(),
+                &["\x1B", "[1", ";4", "mbold\x1B[", "0m"]
+            ),
+            "bold"
+        );
+    }
+
+    #[test]
+    fn test_keep_other_sequences() {
+        assert_eq!(
+            filter(EscapeFilter::sgr(), &["\x1B[2J\x1B[1mclear"]),
+            "\x1B[2Jclear"
+        );
+        assert_eq!(filter(EscapeFilter::sgr(), &["\x1B7saved"]), "\x1B7saved");
+    }
+
+    #[test]
+    fn test_filter_all_sequences() {
+        assert_eq!(
+            filter(
+                EscapeFilter::all(),
+                &["\x1B[2J\x1B[1ma\x1B7b\x1B(Bc\x1B]0;title\x07d\x1B]8;;url\x1B\\e"]
+            ),
+            "abcde"
+        );
+    }
+
+    #[test]
+    fn test_filter_all_split_string() {
+        assert_eq!(
+            filter(
+                EscapeFilter::all(),
+                &["a\x1B]0;ti", "tle\x1B", "\\b\x1B[", "?25", "hc"]
+            ),
+            "abc"
+        );
+    }
+
+    #[test]
+    fn test_filter_all_malformed_sequences() {
+        assert_eq!(
+            filter(EscapeFilter::all(), &["\x1B[1\na\x1B\x01b\x1B(\nc"]),
+            "\na\x01b(\nc"
+        );
+    }
+
+    #[test]
+    fn test_filter_split_utf8() {
+        let mut filter = EscapeFilter::all();
+        let mut output = Vec::new();
+
+        filter.filter("\u{9B}1ma".as_bytes(), &mut output);
+        filter.filter(b"\xC2", &mut output);
+        filter.filter(b"\x9B1mb", &mut output);
+
+        assert_eq!(output, b"ab");
+    }
+
+    #[test]
+    fn test_filter_keeps_invalid_utf8() {
+        let mut output = Vec::new();
+        EscapeFilter::all().filter(b"a\xFF\x1B[1mb\xE2\x82", &mut output);
+
+        assert_eq!(output, b"a\xFFb");
+    }
+}
@@ -0,0 +1,310 @@
+//! This module contains the parser and the formatter of the git color config values.
+
+use crate::error::suggest;
+use crate::{Attribute, Color, ObjectStyle, ParseStyleError, ParseStyleErrorKind};
+
+/// The git color names.
+const GIT_COLORS: [(&str, Color); 16] = [
+    ("black", Color::Black),
+    ("red", Color::DarkRed),
+    ("green", Color::DarkGreen),
+    ("yellow", Color::DarkYellow),
+    ("blue", Color::DarkBlue),
+    ("magenta", Color::DarkMagenta),
+    ("cyan", Color::DarkCyan),
+    ("white", Color::Grey),
+    ("brightblack", Color::DarkGrey),
+    ("brightred", Color::Red),
+    ("brightgreen", Color::Green),
+    ("brightyellow", Color::Yellow),
+    ("brightblue", Color::Blue),
+    ("brightmagenta", Color::Magenta),
+    ("brightcyan", Color::Cyan),
+    ("brightwhite", Color::White),
+];
+
+/// The git attribute names with the attribute turning them on and the one turning them off.
+const GIT_ATTRIBUTES: [(&str, Attribute, Attribute); 7] = [
+    ("bold", Attribute::Bold, Attribute::NormalIntensity),
+    ("dim", Attribute::Dim, Attribute::NormalIntensity),
+    ("ul", Attribute::Underlined, Attribute::NoUnderline),
+    ("blink", Attribute::SlowBlink, Attribute::NoBlink),
+    ("reverse", Attribute::Reverse, Attribute::NoInverse),
+    ("italic", Attribute::Italic, Attribute::NoItalic),
+    ("strike", Attribute::CrossedOut, Attribute::NotCrossedOut),
+];
+
+impl ObjectStyle {
+    /// Parses a git color config value (`color.diff.old = red bold`).
+    ///
+    /// The value is a whitespace separated list of colors and attributes, in any order and
+    /// case. The first color is the foreground color, the second one the background color.
+    ///
+    /// * The colors are `normal` (no color, e.g. `normal blue` only sets the background color),
+    ///   `default`, the 8 ANSI color names (`red` is `Color::DarkRed`), the same names prefixed
+    ///   with `bright` (`brightred` is `Color::Red`), the ANSI values `0` - `255` and the
+    ///   `#rrggbb` & `#rgb` hex colors.
+    /// * The attributes are `bold`, `dim`, `ul`, `blink`, `reverse`, `italic` and `strike`,
+    ///   turned off with the `no` or `no-` prefix (`nobold`, `no-ul`). `reset` resets the
+    ///   colors and attributes before applying the style.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossterm_style::{Attribute, Color, ObjectStyle};
+    ///
+    /// let style = ObjectStyle::from_git("brightred normal bold no-ul").unwrap();
+    ///
+    /// assert_eq!(style.fg_color, Some(Color::Red));
+    /// assert_eq!(style.bg_color, None);
+    /// assert_eq!(style.attrs, Attribute::Bold | Attribute::NoUnderline);
+    ///
+    /// assert_eq!(style.to_git(), "brightred bold noul");
+    /// ```
+    pub fn from_git(src: &str) -> Result<ObjectStyle, ParseStyleError> {
+        let mut style = ObjectStyle::new();
+        let mut colors = 0;
+
+        for (position, word) in words(src) {
+            let lowercase = word.to_lowercase();
+
+            if lowercase == "reset" {
+                style.attrs.insert(Attribute::Reset);
+                continue;
+            }
+
+            if let Some(attr) = parse_attribute(&lowercase) {
+                style.attrs.insert(attr);
+                continue;
+            }
+
+            let color = parse_color(word, &lowercase).map_err(|error| error.offset(position))?;
+
+            match colors {
+                0 => style.fg_color = color,
+                1 => style.bg_color = color,
+                _ => {
+                    return Err(
+                        ParseStyleError::new(word, ParseStyleErrorKind::TooManyColors).at(position),
+                    )
+                }
+            }
+            colors += 1;
+        }
+
+        Ok(style)
+    }
+
+    /// Returns the git color config value of the style.
+    ///
+    /// The foreground color is written first (`normal` if the style has only a background
+    /// color), then the background color and the attributes. The attributes which can't be
+    /// written in a git value (`Fraktur`, `Framed`, ...) are left out.
+    ///
+    /// `nobold` and `nodim` are both read as `NormalIntensity` (`SGR 22`), which is written as
+    /// `nobold`. `ObjectStyle::from_git("nodim").to_git()` returns `"nobold"`, the same style.
+    /// See [`from_git`](#method.from_git) for the syntax.
+    pub fn to_git(&self) -> String {
+        let mut words = Vec::new();
+
+        if self.attrs.contains(Attribute::Reset) {
+            words.push("reset".to_string());
+        }
+
+        match (self.fg_color, self.bg_color) {
+            (Some(fg), Some(bg)) => {
+                words.push(format_color(fg));
+                words.push(format_color(bg));
+            }
+            (Some(fg), None) => words.push(format_color(fg)),
+            (None, Some(bg)) => {
+                words.push("normal".to_string());
+                words.push(format_color(bg));
+            }
+            (None, None) => {}
+        }
+
+        for attr in self.attrs.iter() {
+            let name = GIT_ATTRIBUTES.iter().find_map(|(name, on, off)| {
+                if *on == attr {
+                    Some(name.to_string())
+                } else if *off == attr {
+                    Some(format!("no{}", name))
+                } else {
+                    None
+                }
+            });
+
+            if let Some(name) = name {
+                words.push(name);
+            }
+        }
+
+        words.join(" ")
+    }
+}
+
+/// Splits the input into whitespace separated words with their positions.
+fn words(src: &str) -> impl Iterator<Item = (usize, &str)> {
+    src.split_whitespace()
+        .map(move |word| (word.as_ptr() as usize - src.as_ptr() as usize, word))
+}
+
+fn parse_attribute(name: &str) -> Option<Attribute> {
+    let (name, on) = match name.strip_prefix("no") {
+        Some(name) => (name.strip_prefix('-').unwrap_or(name), false),
+        None => (name, true),
+    };
+
+    GIT_ATTRIBUTES
+        .iter()
+        .find(|(attribute_name, _, _)| *attribute_name == name)
+        .map(|(_, on_attr, off_attr)| if on { *on_attr } else { *off_attr })
+}
+
+/// Parses a git color, `None` for `normal`.
+fn parse_color(word: &str, lowercase: &str) -> Result<Option<Color>, ParseStyleError> {
+    match lowercase {
+        "normal" | "-1" => return Ok(None),
+        "default" => return Ok(Some(Color::Reset)),
+        _ => {}
+    }
+
+    if let Some((_, color)) = GIT_COLORS.iter().find(|(name, _)| *name == lowercase) {
+        return Ok(Some(*color));
+    }
+
+    if lowercase.starts_with('#') {
+        return word.parse().map(Some);
+    }
+
+    if lowercase.chars().all(|c| c.is_ascii_digit()) {
+        return match lowercase.parse::<u8>() {
+            Ok(value) => Ok(Some(Color::AnsiValue(value))),
+            Err(_) => Err(ParseStyleError::new(word, ParseStyleErrorKind::OutOfRange)),
+        };
+    }
+
+    let names = ["normal", "default", "reset"]
+        .iter()
+        .copied()
+        .chain(GIT_COLORS.iter().map(|(name, _)| *name))
+        .chain(GIT_ATTRIBUTES.iter().map(|(name, _, _)| *name));
+
+    Err(ParseStyleError::new(word, ParseStyleErrorKind::UnknownName)
+        .with_suggestion(suggest(lowercase, names)))
+}
+
+fn format_color(color: Color) -> String {
+    match color {
+        Color::Reset => "default".to_string(),
+        Color::AnsiValue(value) => value.to_string(),
+        Color::Rgb { r, g, b } => format!("#{:02x}{:02x}{:02x}", r, g, b),
+        _ => GIT_COLORS
+            .iter()
+            .find(|(_, named)| *named == color)
+            .map(|(name, _)| name.to_string())
+            .unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Attribute, Color, ObjectStyle, ParseStyleErrorKind};
+
+    #[test]
+    fn test_parse_colors() {
+        let style = ObjectStyle::from_git("red blue").unwrap();
+        assert_eq!(style.fg_color, Some(Color::DarkRed));
+        assert_eq!(style.bg_color, Some(Color::DarkBlue));
+
+        let style = ObjectStyle::from_git("normal BrightWhite").unwrap();
+        assert_eq!(style.fg_color, None);
+        assert_eq!(style.bg_color, Some(Color::White));
+
+        let style = ObjectStyle::from_git("208 #f80").unwrap();
+        assert_eq!(style.fg_color, Some(Color::AnsiValue(208)));
+        assert_eq!(
+            style.bg_color,
+            Some(Color::Rgb {
+                r: 255,
+                g: 136,
+                b: 0
+            })
+        );
+
+        let style = ObjectStyle::from_git("default -1").unwrap();
+        assert_eq!(style.fg_color, Some(Color::Reset));
+        assert_eq!(style.bg_color, None);
+    }
+
+    #[test]
+    fn test_parse_attributes() {
+        let style = ObjectStyle::from_git("reset bold ul nodim no-italic strike").unwrap();
+
+        assert_eq!(
+            style.attrs,
+            Attribute::Reset
+                | Attribute::NormalIntensity
+                | Attribute::Underlined
+                | Attribute::NoItalic
+                | Attribute::CrossedOut
+        );
+        assert!(ObjectStyle::from_git("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = ObjectStyle::from_git("bold redd").unwrap_err();
+        assert_eq!(error.kind(), ParseStyleErrorKind::UnknownName);
+        assert_eq!(error.position(), 5);
+        assert_eq!(error.suggestion(), Some("red"));
+
+        let error = ObjectStyle::from_git("red blue  green").unwrap_err();
+        assert_eq!(error.kind(), ParseStyleErrorKind::TooManyColors);
+        assert_eq!(error.position(), 10);
+
+        let error = ObjectStyle::from_git("256").unwrap_err();
+        assert_eq!(error.kind(), ParseStyleErrorKind::OutOfRange);
+
+        let error = ObjectStyle::from_git("red #ff00zz").unwrap_err();
+        assert_eq!(error.kind(), ParseStyleErrorKind::InvalidHex);
+        assert_eq!(error.position(), 4);
+    }
+
+    #[test]
+    fn test_format_round_trip() {
+        for value in &[
+            "red",
+            "brightblack white",
+            "normal blue",
+            "default 17",
+            "#010203 bold",
+            "reset yellow blink reverse",
+            "nobold noitalic noul noblink noreverse nostrike",
+            "",
+        ] {
+            let style = ObjectStyle::from_git(value).unwrap();
+
+            assert_eq!(style.to_git(), *value);
+            assert_eq!(ObjectStyle::from_git(&style.to_git()).unwrap(), style);
+        }
+    }
+
+    #[test]
+    fn test_format_nodim_as_nobold() {
+        let style = ObjectStyle::from_git("nodim").unwrap();
+
+        assert_eq!(style.to_git(), "nobold");
+        assert_eq!(ObjectStyle::from_git("nobold").unwrap(), style);
+    }
+
+    #[test]
+    fn test_format_skips_unsupported_attributes() {
+        let mut style = ObjectStyle::new().fg(Color::Green);
+        style.add_attr(Attribute::Fraktur);
+        style.add_attr(Attribute::Bold);
+
+        assert_eq!(style.to_git(), "brightgreen bold");
+    }
+}
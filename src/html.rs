@@ -0,0 +1,471 @@
+//! This module contains the renderer writing the styled text as HTML.
+
+use std::fmt::Write;
+
+use crate::enums::COLOR_NAMES;
+use crate::{Attribute, Color, ObjectStyle, Palette, StyledText};
+
+/// The attributes written as CSS, with their class name and declaration.
+const ATTRIBUTE_CSS: [(Attribute, &str, &str); 4] = [
+    (Attribute::Bold, "bold", "font-weight:bold"),
+    (Attribute::Dim, "dim", "opacity:0.5"),
+    (Attribute::Italic, "italic", "font-style:italic"),
+    (Attribute::Hidden, "hidden", "visibility:hidden"),
+];
+
+/// The text decorations with their class name and value.
+///
+/// The decorations of a span are combined in a single declaration, a CSS rule per decoration
+/// would override the other ones. The class of several decorations joins their names
+/// (`underlined-crossed-out`).
+const TEXT_DECORATIONS: [(Attribute, &str, &str); 4] = [
+    (Attribute::Underlined, "underlined", "underline"),
+    (Attribute::CrossedOut, "crossed-out", "line-through"),
+    (Attribute::OverLined, "overlined", "overline"),
+    (Attribute::SlowBlink, "blink", "blink"),
+];
+
+/// A color of a span, the default colors are kept apart to be swapped by `Reverse`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Paint {
+    DefaultForeground,
+    DefaultBackground,
+    Color(Color),
+}
+
+/// A renderer writing the styled text as HTML.
+///
+/// The text is written in a `<pre>` element, each span with a style in a `<span>` element. The
+/// styles are written either as inline styles (`style="color:#cd0000"`, the default) or as CSS
+/// classes (`class="ansi-fg-dark-red"`, see [`classes`](#method.classes) and
+/// [`stylesheet`](#method.stylesheet)). The named colors are turned into RGB values with the
+/// [`Palette`](struct.Palette.html), the colors without a class are written inline.
+///
+/// The `Bold`, `Dim`, `Italic`, `Underlined`, `CrossedOut`, `OverLined`,
+/// `SlowBlink`/`RapidBlink` & `Hidden` attributes are written as CSS properties, `Reverse` swaps
+/// the colors.
+///
+/// # Examples
+///
+/// ```
+/// use crossterm_style::{Colorize, Html, StyledText};
+///
+/// let text = StyledText::from("error:".red()) + " file <a> not found";
+///
+/// assert_eq!(
+///     Html::new().render(&text),
+///     "<pre style=\"color:#e5e5e5;background-color:#000000\">\
+///      <span style=\"color:#ff0000\">error:</span> file &lt;a&gt; not found</pre>"
+/// );
+///
+/// assert_eq!(
+///     Html::new().classes("log").render(&text),
+///     "<pre class=\"log\"><span class=\"log-fg-red\">error:</span> file &lt;a&gt; not found</pre>"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Html {
+    palette: Palette,
+    class_prefix: Option<String>,
+}
+
+impl Html {
+    /// Creates a new `Html` renderer writing inline styles with the xterm palette.
+    pub fn new() -> Html {
+        Html {
+            palette: Palette::xterm(),
+            class_prefix: None,
+        }
+    }
+
+    /// Sets the palette used to turn the colors into RGB values.
+    pub fn palette(mut self, palette: Palette) -> Html {
+        self.palette = palette;
+        self
+    }
+
+    /// Writes the styles as CSS classes starting with the given prefix.
+    ///
+    /// The `<pre>` element has the `prefix` class, the spans have the `prefix-fg-red`,
+    /// `prefix-bg-dark-blue`, `prefix-bold`, ... classes. The text decorations of a span are
+    /// combined in a single class (`prefix-underlined-crossed-out`). See
+    /// [`stylesheet`](#method.stylesheet) for the CSS rules of the classes.
+    pub fn classes(mut self, prefix: &str) -> Html {
+        self.class_prefix = Some(prefix.to_string());
+        self
+    }
+
+    /// Returns the text written as HTML.
+    ///
+    /// The escape sequences nested in the spans are parsed, their styles are applied on top of
+    /// the style of the span.
+    pub fn render(&self, text: &StyledText) -> String {
+        let mut html = String::new();
+
+        match &self.class_prefix {
+            Some(prefix) => {
+                let _ = write!(html, "<pre class=\"{}\">", prefix);
+            }
+            None => {
+                let _ = write!(
+                    html,
+                    "<pre style=\"color:{};background-color:{}\">",
+                    hex(self.palette.foreground),
+                    hex(self.palette.background)
+                );
+            }
+        }
+
+        for (style, content) in text.pieces() {
+            let attributes = self.span_attributes(&style);
+
+            if attributes.is_empty() {
+                escape(&mut html, content);
+            } else {
+                let _ = write!(html, "<span{}>", attributes);
+                escape(&mut html, content);
+                html.push_str("</span>");
+            }
+        }
+
+        html.push_str("</pre>");
+        html
+    }
+
+    /// Returns the input containing ANSI escape sequences written as HTML.
+    ///
+    /// See [`parse_ansi`](fn.parse_ansi.html) for more info.
+    pub fn render_ansi(&self, input: &str) -> String {
+        self.render(&StyledText::from_ansi(input))
+    }
+
+    /// Returns the CSS rules of the classes, see [`classes`](#method.classes).
+    ///
+    /// Returns an empty string if the styles are written inline.
+    pub fn stylesheet(&self) -> String {
+        let prefix = match &self.class_prefix {
+            Some(prefix) => prefix,
+            None => return String::new(),
+        };
+
+        let mut css = format!(
+            ".{} {{ color: {}; background-color: {}; }}\n",
+            prefix,
+            hex(self.palette.foreground),
+            hex(self.palette.background)
+        );
+
+        for (name, color) in COLOR_NAMES
+            .iter()
+            .filter(|(_, color)| *color != Color::Reset)
+        {
+            // safe unwrap, the named colors have an RGB value
+            let rgb = hex(self.palette.rgb(*color).unwrap());
+            let name = name.replace('_', "-");
+
+            let _ = writeln!(css, ".{}-fg-{} {{ color: {}; }}", prefix, name, rgb);
+            let _ = writeln!(
+                css,
+                ".{}-bg-{} {{ background-color: {}; }}",
+                prefix, name, rgb
+            );
+        }
+
+        for (_, name, declaration) in ATTRIBUTE_CSS.iter() {
+            let _ = writeln!(css, ".{}-{} {{ {}; }}", prefix, name, declaration);
+        }
+
+        // a class for each combination of the decorations
+        for mask in 1..1 << TEXT_DECORATIONS.len() {
+            let decorations: Vec<_> = TEXT_DECORATIONS
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| mask & (1 << index) != 0)
+                .map(|(_, decoration)| decoration)
+                .collect();
+
+            let _ = writeln!(
+                css,
+                ".{}-{} {{ text-decoration-line:{}; }}",
+                prefix,
+                decorations
+                    .iter()
+                    .map(|(_, name, _)| *name)
+                    .collect::<Vec<_>>()
+                    .join("-"),
+                decorations
+                    .iter()
+                    .map(|(_, _, value)| *value)
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            );
+        }
+
+        css
+    }
+
+    /// Returns the HTML attributes (`class` and `style`) of a span.
+    fn span_attributes(&self, style: &ObjectStyle) -> String {
+        let mut classes = Vec::new();
+        let mut declarations = Vec::new();
+
+        let mut fg = match style.fg_color {
+            Some(Color::Reset) | None => Paint::DefaultForeground,
+            Some(color) => Paint::Color(color),
+        };
+        let mut bg = match style.bg_color {
+            Some(Color::Reset) | None => Paint::DefaultBackground,
+            Some(color) => Paint::Color(color),
+        };
+
+        if style.attrs.contains(Attribute::Reverse) {
+            std::mem::swap(&mut fg, &mut bg);
+        }
+
+        for (paint, side, property, default) in [
+            (fg, "fg", "color", Paint::DefaultForeground),
+            (bg, "bg", "background-color", Paint::DefaultBackground),
+        ] {
+            if paint == default {
+                continue;
+            }
+
+            match (&self.class_prefix, paint) {
+                (Some(prefix), Paint::Color(color)) if color_name(color).is_some() => {
+                    // safe unwrap, checked by the guard
+                    classes.push(format!(
+                        "{}-{}-{}",
+                        prefix,
+                        side,
+                        color_name(color).unwrap()
+                    ));
+                }
+                _ => declarations.push(format!("{}:{}", property, hex(self.paint_rgb(paint)))),
+            }
+        }
+
+        let mut attrs = style.attrs;
+        if attrs.contains(Attribute::RapidBlink) {
+            attrs.insert(Attribute::SlowBlink);
+        }
+
+        let decorations: Vec<_> = TEXT_DECORATIONS
+            .iter()
+            .filter(|(attribute, _, _)| attrs.contains(*attribute))
+            .collect();
+
+        match &self.class_prefix {
+            Some(prefix) => {
+                for (attribute, name, _) in ATTRIBUTE_CSS.iter() {
+                    if attrs.contains(*attribute) {
+                        classes.push(format!("{}-{}", prefix, name));
+                    }
+                }
+
+                if !decorations.is_empty() {
+                    let names: Vec<&str> = decorations.iter().map(|(_, name, _)| *name).collect();
+                    classes.push(format!("{}-{}", prefix, names.join("-")));
+                }
+            }
+            None => {
+                for (attribute, _, declaration) in ATTRIBUTE_CSS.iter() {
+                    if attrs.contains(*attribute) {
+                        declarations.push(declaration.to_string());
+                    }
+                }
+
+                if !decorations.is_empty() {
+                    let values: Vec<&str> =
+                        decorations.iter().map(|(_, _, value)| *value).collect();
+                    declarations.push(format!("text-decoration:{}", values.join(" ")));
+                }
+            }
+        }
+
+        let mut attributes = String::new();
+        if !classes.is_empty() {
+            let _ = write!(attributes, " class=\"{}\"", classes.join(" "));
+        }
+        if !declarations.is_empty() {
+            let _ = write!(attributes, " style=\"{}\"", declarations.join(";"));
+        }
+        attributes
+    }
+
+    fn paint_rgb(&self, paint: Paint) -> (u8, u8, u8) {
+        match paint {
+            Paint::DefaultForeground => self.palette.foreground,
+            Paint::DefaultBackground => self.palette.background,
+            Paint::Color(color) => self.palette.rgb(color).unwrap_or(self.palette.foreground),
+        }
+    }
+}
+
+impl Default for Html {
+    fn default() -> Self {
+        Html::new()
+    }
+}
+
+/// Returns the CSS class name of a named color (`dark-red`).
+fn color_name(color: Color) -> Option<String> {
+    COLOR_NAMES
+        .iter()
+        .filter(|(_, named)| *named != Color::Reset)
+        .find(|(_, named)| *named == color)
+        .map(|(name, _)| name.replace('_', "-"))
+}
+
+/// Returns the CSS hex notation of an RGB value (`#cd0000`).
+pub(crate) fn hex((r, g, b): (u8, u8, u8)) -> String {
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+/// Appends the text with the HTML special characters escaped.
+pub(crate) fn escape(output: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '&' => output.push_str("&amp;"),
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            '"' => output.push_str("&quot;"),
+            '\'' => output.push_str("&#39;"),
+            _ => output.push(c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Attribute, Color, Colorize, Html, ObjectStyle, Palette, Span, StyledText, Styler};
+
+    fn render_span(html: &Html, span: Span) -> String {
+        html.render(&span.into())
+    }
+
+    #[test]
+    fn test_inline_attributes() {
+        let html = Html::new();
+
+        assert_eq!(
+            render_span(&html, "a".bold().italic().dim().into()),
+            "<pre style=\"color:#e5e5e5;background-color:#000000\">\
+             <span style=\"font-weight:bold;opacity:0.5;font-style:italic\">a</span></pre>"
+        );
+        assert_eq!(
+            render_span(&html, "a".underlined().crossed_out().into()),
+            "<pre style=\"color:#e5e5e5;background-color:#000000\">\
+             <span style=\"text-decoration:underline line-through\">a</span></pre>"
+        );
+    }
+
+    #[test]
+    fn test_inline_colors_with_palette() {
+        let html = Html::new().palette(Palette::vga());
+        let style = ObjectStyle::new()
+            .fg(Color::AnsiValue(196))
+            .bg(Color::DarkYellow);
+
+        assert_eq!(
+            render_span(&html, Span::new("a", style)),
+            "<pre style=\"color:#aaaaaa;background-color:#000000\">\
+             <span style=\"color:#ff0000;background-color:#aa5500\">a</span></pre>"
+        );
+    }
+
+    #[test]
+    fn test_reverse() {
+        let html = Html::new();
+
+        assert_eq!(
+            render_span(&html, "a".reverse().into()),
+            "<pre style=\"color:#e5e5e5;background-color:#000000\">\
+             <span style=\"color:#000000;background-color:#e5e5e5\">a</span></pre>"
+        );
+
+        let html = Html::new().classes("ansi");
+        let span: Span = "a".red().attr(Attribute::Reverse).into();
+
+        assert_eq!(
+            render_span(&html, span),
+            "<pre class=\"ansi\">\
+             <span class=\"ansi-bg-red\" style=\"color:#000000\">a</span></pre>"
+        );
+    }
+
+    #[test]
+    fn test_classes() {
+        let html = Html::new().classes("ansi");
+        let style = ObjectStyle::new()
+            .fg(Color::DarkBlue)
+            .bg(Color::Rgb { r: 1, g: 2, b: 3 });
+        let text = StyledText::from(Span::new("a", style)) + "b".underlined().crossed_out();
+
+        assert_eq!(
+            html.render(&text),
+            "<pre class=\"ansi\">\
+             <span class=\"ansi-fg-dark-blue\" style=\"background-color:#010203\">a</span>\
+             <span class=\"ansi-underlined-crossed-out\">b</span></pre>"
+        );
+    }
+
+    #[test]
+    fn test_class_decorations() {
+        let html = Html::new().classes("ansi");
+        let span: Span = "a"
+            .bold()
+            .attr(Attribute::RapidBlink)
+            .attr(Attribute::OverLined)
+            .into();
+
+        assert_eq!(
+            render_span(&html, span),
+            "<pre class=\"ansi\">\
+             <span class=\"ansi-bold ansi-overlined-blink\">a</span></pre>"
+        );
+        assert!(html
+            .stylesheet()
+            .contains(".ansi-overlined-blink { text-decoration-line:overline blink; }\n"));
+    }
+
+    #[test]
+    fn test_stylesheet() {
+        assert_eq!(Html::new().stylesheet(), "");
+
+        let css = Html::new().classes("ansi").stylesheet();
+
+        assert!(css.starts_with(".ansi { color: #e5e5e5; background-color: #000000; }\n"));
+        assert!(css.contains(".ansi-fg-dark-red { color: #cd0000; }\n"));
+        assert!(css.contains(".ansi-bg-white { background-color: #ffffff; }\n"));
+        assert!(css.contains(".ansi-crossed-out { text-decoration-line:line-through; }\n"));
+        assert!(css.contains(
+            ".ansi-underlined-crossed-out { text-decoration-line:underline line-through; }\n"
+        ));
+        assert!(css.contains(
+            ".ansi-underlined-crossed-out-overlined-blink \
+             { text-decoration-line:underline line-through overline blink; }\n"
+        ));
+    }
+
+    #[test]
+    fn test_nested_escape_sequences() {
+        let span = Span::new("a\x1B[1mb\x1B[22m", ObjectStyle::new().fg(Color::DarkRed));
+
+        assert_eq!(
+            render_span(&Html::new().classes("ansi"), span),
+            "<pre class=\"ansi\"><span class=\"ansi-fg-dark-red\">a</span>\
+             <span class=\"ansi-fg-dark-red ansi-bold\">b</span></pre>"
+        );
+    }
+
+    #[test]
+    fn test_render_ansi() {
+        assert_eq!(
+            Html::new()
+                .classes("ansi")
+                .render_ansi("\x1B[1;31m<b>\x1B[0m & \"c\""),
+            "<pre class=\"ansi\"><span class=\"ansi-fg-dark-red ansi-bold\">&lt;b&gt;</span> \
+             &amp; &quot;c&quot;</pre>"
+        );
+    }
+}
@@ -110,7 +110,6 @@
 //! );
 //! ```
 
-use std::env;
 use std::fmt::Display;
 
 #[cfg(windows)]
@@ -125,18 +124,54 @@ use style::ansi::{self, AnsiColor};
 use style::winapi::WinApiColor;
 use style::Style;
 
-pub use self::enums::{Attribute, Color, Colored};
+pub use self::background::{detect_background, Background};
+pub use self::depth::{color_depth, set_color_depth, ColorDepth};
+pub use self::diff::sgr_diff;
+pub use self::enums::{Attribute, Attributes, AttributesIter, Color, Colored};
+pub use self::error::{ParseStyleError, ParseStyleErrorKind};
+pub use self::html::Html;
+pub use self::lscolors::{Indicator, LsColors};
 pub use self::objectstyle::ObjectStyle;
-pub use self::styledobject::StyledObject;
+pub use self::palette::Palette;
+pub use self::parser::{parse_ansi, AnsiSpans};
+pub use self::policy::{color_choice, set_color_choice, should_colorize, ColorChoice, ColorWriter};
+pub use self::query::{parse_color_response, query_color, ColorQuery};
+pub use self::strip::{strip_ansi, StripWriter};
+pub use self::styledobject::{Padding, StyledObject};
+pub use self::support::ColorSupport;
+pub use self::svg::Svg;
+pub use self::text::{Span, StyledText};
+pub use self::theme::Theme;
 pub use self::traits::{Colorize, Styler};
+pub use self::width::ansi_width;
 
 #[macro_use]
 mod macros;
+mod background;
+mod depth;
+mod diff;
 mod enums;
+mod error;
+mod filter;
+mod git;
+mod html;
+mod lscolors;
 mod objectstyle;
+mod palette;
+mod parser;
+mod policy;
+mod query;
+#[cfg(feature = "serde")]
+mod serialize;
+mod strip;
 mod style;
 mod styledobject;
+mod support;
+mod svg;
+mod text;
+mod theme;
 mod traits;
+mod width;
 
 /// Creates a `StyledObject`.
 ///
@@ -155,7 +190,7 @@ mod traits;
 ///
 /// println!("{}", styled_object);
 /// ```
-pub fn style<'a, D: 'a>(val: D) -> StyledObject<D>
+pub fn style<D>(val: D) -> StyledObject<D>
 where
     D: Display + Clone,
 {
@@ -244,6 +279,7 @@ pub struct TerminalColor {
 
 impl TerminalColor {
     /// Creates a new `TerminalColor`.
+    #[allow(clippy::new_without_default)]
     pub fn new() -> TerminalColor {
         #[cfg(windows)]
         let color = if supports_ansi() {
@@ -273,15 +309,25 @@ impl TerminalColor {
         self.color.reset()
     }
 
+    /// Returns the color support of the terminal.
+    ///
+    /// See [`ColorSupport::detect`](enum.ColorSupport.html#method.detect) for more info.
+    pub fn color_support(&self) -> ColorSupport {
+        ColorSupport::detect()
+    }
+
     /// Returns available color count.
     ///
     /// # Notes
     ///
-    /// This does not always provide a good result.
+    /// The count saturates at `u16::MAX` for the terminals supporting 24-bit colors. It's at
+    /// least 8, even if the colors are not supported (see
+    /// [`color_support`](#method.color_support)).
+    #[deprecated(note = "use `color_support` instead")]
     pub fn available_color_count(&self) -> u16 {
-        env::var("TERM")
-            .map(|x| if x.contains("256color") { 256 } else { 8 })
-            .unwrap_or(8)
+        self.color_support()
+            .color_count()
+            .clamp(8, u32::from(u16::MAX)) as u16
     }
 }
 
@@ -314,18 +360,27 @@ pub fn color() -> TerminalColor {
 ///
 /// # Notes
 ///
-/// Commands must be executed/queued for execution otherwise they do nothing.
+/// * Commands must be executed/queued for execution otherwise they do nothing.
+/// * Nothing is written if the global [`ColorChoice`](enum.ColorChoice.html) disables the styles.
 pub struct SetFg(pub Color);
 
 impl Command for SetFg {
     type AnsiType = String;
 
     fn ansi_code(&self) -> Self::AnsiType {
+        if !should_colorize() {
+            return String::new();
+        }
+
         ansi::set_fg_csi_sequence(self.0)
     }
 
     #[cfg(windows)]
     fn execute_winapi(&self) -> Result<()> {
+        if !should_colorize() {
+            return Ok(());
+        }
+
         WinApiColor::new().set_fg(self.0)
     }
 }
@@ -336,18 +391,27 @@ impl Command for SetFg {
 ///
 /// # Notes
 ///
-/// Commands must be executed/queued for execution otherwise they do nothing.
+/// * Commands must be executed/queued for execution otherwise they do nothing.
+/// * Nothing is written if the global [`ColorChoice`](enum.ColorChoice.html) disables the styles.
 pub struct SetBg(pub Color);
 
 impl Command for SetBg {
     type AnsiType = String;
 
     fn ansi_code(&self) -> Self::AnsiType {
+        if !should_colorize() {
+            return String::new();
+        }
+
         ansi::set_bg_csi_sequence(self.0)
     }
 
     #[cfg(windows)]
     fn execute_winapi(&self) -> Result<()> {
+        if !should_colorize() {
+            return Ok(());
+        }
+
         WinApiColor::new().set_bg(self.0)
     }
 }
@@ -358,13 +422,18 @@ impl Command for SetBg {
 ///
 /// # Notes
 ///
-/// Commands must be executed/queued for execution otherwise they do nothing.
+/// * Commands must be executed/queued for execution otherwise they do nothing.
+/// * Nothing is written if the global [`ColorChoice`](enum.ColorChoice.html) disables the styles.
 pub struct SetAttr(pub Attribute);
 
 impl Command for SetAttr {
     type AnsiType = String;
 
     fn ansi_code(&self) -> Self::AnsiType {
+        if !should_colorize() {
+            return String::new();
+        }
+
         ansi::set_attr_csi_sequence(self.0)
     }
 
@@ -375,6 +444,139 @@ impl Command for SetAttr {
     }
 }
 
+/// A command to set the colors and attributes of the style.
+///
+/// All the colors and attributes are set with a single escape sequence, the current ones
+/// which are not part of the style are left untouched.
+///
+/// See [`ObjectStyle`](struct.ObjectStyle.html) for more info.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::io::{stdout, Write};
+///
+/// use crossterm_utils::{queue, Result, Output};
+/// use crossterm_style::{ObjectStyle, ResetColor, SetStyle};
+///
+/// fn main() -> Result<()> {
+///     let style: ObjectStyle = "bold yellow on dark_blue".parse().unwrap();
+///
+///     queue!(
+///         stdout(),
+///         SetStyle(style),
+///         Output("Styled text here.".to_string()),
+///         ResetColor
+///     )
+/// }
+/// ```
+///
+/// # Notes
+///
+/// * Commands must be executed/queued for execution otherwise they do nothing.
+/// * Nothing is written if the global [`ColorChoice`](enum.ColorChoice.html) disables the styles.
+/// * Attributes are not supported by WinAPI.
+pub struct SetStyle(pub ObjectStyle);
+
+impl Command for SetStyle {
+    type AnsiType = String;
+
+    fn ansi_code(&self) -> Self::AnsiType {
+        if !should_colorize() {
+            return String::new();
+        }
+
+        ansi::set_style_csi_sequence(&self.0)
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> Result<()> {
+        if !should_colorize() {
+            return Ok(());
+        }
+
+        if self.0.attrs.contains(Attribute::Reset) {
+            WinApiColor::new().reset()?;
+        }
+        if let Some(bg) = self.0.bg_color {
+            WinApiColor::new().set_bg(bg)?;
+        }
+        if let Some(fg) = self.0.fg_color {
+            WinApiColor::new().set_fg(fg)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A command to set the foreground and background colors.
+///
+/// Both colors are set with a single escape sequence, a `None` color is left untouched.
+///
+/// # Notes
+///
+/// * Commands must be executed/queued for execution otherwise they do nothing.
+/// * Nothing is written if the global [`ColorChoice`](enum.ColorChoice.html) disables the styles.
+pub struct SetColors {
+    /// The foreground color.
+    pub fg: Option<Color>,
+    /// The background color.
+    pub bg: Option<Color>,
+}
+
+impl Command for SetColors {
+    type AnsiType = String;
+
+    fn ansi_code(&self) -> Self::AnsiType {
+        SetStyle(ObjectStyle {
+            fg_color: self.fg,
+            bg_color: self.bg,
+            ..Default::default()
+        })
+        .ansi_code()
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> Result<()> {
+        SetStyle(ObjectStyle {
+            fg_color: self.fg,
+            bg_color: self.bg,
+            ..Default::default()
+        })
+        .execute_winapi()
+    }
+}
+
+/// A command to set the text attributes.
+///
+/// All the attributes are set with a single escape sequence.
+///
+/// See [`Attributes`](struct.Attributes.html) for more info.
+///
+/// # Notes
+///
+/// * Commands must be executed/queued for execution otherwise they do nothing.
+/// * Nothing is written if the global [`ColorChoice`](enum.ColorChoice.html) disables the styles.
+pub struct SetAttributes(pub Attributes);
+
+impl Command for SetAttributes {
+    type AnsiType = String;
+
+    fn ansi_code(&self) -> Self::AnsiType {
+        SetStyle(ObjectStyle {
+            attrs: self.0,
+            ..Default::default()
+        })
+        .ansi_code()
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> Result<()> {
+        // attributes are not supported by WinAPI.
+        Ok(())
+    }
+}
+
 /// A command to print the styled object.
 ///
 /// See [`StyledObject`](struct.StyledObject.html) for more info.
@@ -404,18 +606,27 @@ where
 ///
 /// # Notes
 ///
-/// Commands must be executed/queued for execution otherwise they do nothing.
+/// * Commands must be executed/queued for execution otherwise they do nothing.
+/// * Nothing is written if the global [`ColorChoice`](enum.ColorChoice.html) disables the styles.
 pub struct ResetColor;
 
 impl Command for ResetColor {
     type AnsiType = String;
 
     fn ansi_code(&self) -> Self::AnsiType {
+        if !should_colorize() {
+            return String::new();
+        }
+
         ansi::RESET_CSI_SEQUENCE.to_string()
     }
 
     #[cfg(windows)]
     fn execute_winapi(&self) -> Result<()> {
+        if !should_colorize() {
+            return Ok(());
+        }
+
         WinApiColor::new().reset()
     }
 }
@@ -423,6 +634,9 @@ impl Command for ResetColor {
 impl_display!(for SetFg);
 impl_display!(for SetBg);
 impl_display!(for SetAttr);
+impl_display!(for SetStyle);
+impl_display!(for SetColors);
+impl_display!(for SetAttributes);
 impl_display!(for PrintStyledFont<String>);
 impl_display!(for PrintStyledFont<&'static str>);
 impl_display!(for ResetColor);
@@ -0,0 +1,493 @@
+//! This module contains the `LS_COLORS` parser styling the files like `ls` does.
+
+use std::collections::HashMap;
+use std::env;
+use std::fs::{self, Metadata};
+use std::path::Path;
+
+use crate::style::ansi::apply_sgr_params;
+use crate::ObjectStyle;
+
+/// The default styles of `ls` when `LS_COLORS` is not set.
+const DEFAULT_LS_COLORS: &str =
+    "di=01;34:ln=01;36:pi=33:so=01;35:do=01;35:bd=01;33:cd=01;33:ex=01;32";
+
+/// A file type, or a file property, styled by the `LS_COLORS` two letter entries (`di=01;34`).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Indicator {
+    /// `no`, the fallback of all the other indicators.
+    Normal,
+    /// `fi`, a regular file.
+    RegularFile,
+    /// `di`, a directory.
+    Directory,
+    /// `ln`, a symbolic link. The value `target` styles the link like its target.
+    SymbolicLink,
+    /// `mh`, a regular file with multiple hard links.
+    MultipleHardLinks,
+    /// `pi`, a named pipe (FIFO).
+    Fifo,
+    /// `so`, a socket.
+    Socket,
+    /// `do`, a door.
+    Door,
+    /// `bd`, a block device.
+    BlockDevice,
+    /// `cd`, a character device.
+    CharacterDevice,
+    /// `or`, a symbolic link to a missing file (orphan).
+    OrphanedSymbolicLink,
+    /// `mi`, a missing file pointed by an orphaned symbolic link.
+    MissingFile,
+    /// `su`, a file with the setuid bit.
+    Setuid,
+    /// `sg`, a file with the setgid bit.
+    Setgid,
+    /// `ca`, a file with capabilities.
+    Capabilities,
+    /// `tw`, a directory with the sticky bit, writable by others.
+    StickyOtherWritable,
+    /// `ow`, a directory writable by others.
+    OtherWritable,
+    /// `st`, a directory with the sticky bit.
+    Sticky,
+    /// `ex`, an executable file.
+    Executable,
+}
+
+/// The two letter codes of the indicators.
+const INDICATOR_CODES: [(&str, Indicator); 19] = [
+    ("no", Indicator::Normal),
+    ("fi", Indicator::RegularFile),
+    ("di", Indicator::Directory),
+    ("ln", Indicator::SymbolicLink),
+    ("mh", Indicator::MultipleHardLinks),
+    ("pi", Indicator::Fifo),
+    ("so", Indicator::Socket),
+    ("do", Indicator::Door),
+    ("bd", Indicator::BlockDevice),
+    ("cd", Indicator::CharacterDevice),
+    ("or", Indicator::OrphanedSymbolicLink),
+    ("mi", Indicator::MissingFile),
+    ("su", Indicator::Setuid),
+    ("sg", Indicator::Setgid),
+    ("ca", Indicator::Capabilities),
+    ("tw", Indicator::StickyOtherWritable),
+    ("ow", Indicator::OtherWritable),
+    ("st", Indicator::Sticky),
+    ("ex", Indicator::Executable),
+];
+
+impl Indicator {
+    /// Returns the indicator of the two letter code (`di`).
+    pub fn from_code(code: &str) -> Option<Indicator> {
+        INDICATOR_CODES
+            .iter()
+            .find(|(indicator_code, _)| *indicator_code == code)
+            .map(|(_, indicator)| *indicator)
+    }
+
+    /// Returns the two letter code of the indicator (`di`).
+    pub fn code(self) -> &'static str {
+        INDICATOR_CODES
+            .iter()
+            .find(|(_, indicator)| *indicator == self)
+            .map(|(code, _)| *code)
+            .unwrap_or_default()
+    }
+}
+
+/// The styles of the files from the `LS_COLORS` environment variable.
+///
+/// `LS_COLORS` is a colon separated list of `key=value` entries, generated by `dircolors`. The
+/// keys are either two letter codes of the file types (see [`Indicator`](enum.Indicator.html))
+/// or file name suffixes (`*.tar`, `*README`), the values are SGR parameters (`01;34`). The
+/// malformed entries and the unknown codes are ignored.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::path::Path;
+///
+/// use crossterm_style::LsColors;
+///
+/// let ls_colors = LsColors::from_env();
+/// let path = Path::new("Cargo.toml");
+///
+/// let style = ls_colors.style_for_path(path).unwrap_or_default();
+/// println!("{}", style.apply_to(path.display().to_string()));
+/// ```
+///
+/// ```
+/// use crossterm_style::{Color, Indicator, LsColors};
+///
+/// let ls_colors = LsColors::parse("di=01;34:*.tar=38;5;9");
+///
+/// assert_eq!(
+///     ls_colors.indicator(Indicator::Directory).unwrap().to_string(),
+///     "bold dark_blue"
+/// );
+/// assert_eq!(
+///     ls_colors.style_for_name("backup.TAR").unwrap().fg_color,
+///     Some(Color::Red)
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LsColors {
+    indicators: HashMap<Indicator, ObjectStyle>,
+    /// The file name suffixes, in the order of `LS_COLORS`.
+    suffixes: Vec<(String, ObjectStyle)>,
+    /// `ln=target` styles the symbolic links like their target.
+    link_as_target: bool,
+}
+
+impl LsColors {
+    /// Parses the `LS_COLORS` value.
+    pub fn parse(ls_colors: &str) -> LsColors {
+        let mut colors = LsColors::default();
+
+        for entry in ls_colors.split(':') {
+            let (key, value) = match entry.find('=') {
+                Some(index) => (&entry[..index], &entry[index + 1..]),
+                None => continue,
+            };
+
+            if key == "ln" && value == "target" {
+                colors.link_as_target = true;
+                continue;
+            }
+
+            if !value
+                .chars()
+                .all(|c| c.is_ascii_digit() || c == ';' || c == ':')
+            {
+                continue;
+            }
+
+            let mut style = ObjectStyle::new();
+            apply_sgr_params(&mut style, value);
+
+            if let Some(suffix) = key.strip_prefix('*') {
+                colors.suffixes.retain(|(existing, _)| existing != suffix);
+                colors.suffixes.push((suffix.to_string(), style));
+            } else if let Some(indicator) = Indicator::from_code(key) {
+                colors.indicators.insert(indicator, style);
+            }
+        }
+
+        colors
+    }
+
+    /// Reads the `LS_COLORS` environment variable, the default styles of `ls` are used if it's
+    /// not set.
+    pub fn from_env() -> LsColors {
+        match env::var("LS_COLORS") {
+            Ok(ls_colors) if !ls_colors.is_empty() => LsColors::parse(&ls_colors),
+            _ => LsColors::parse(DEFAULT_LS_COLORS),
+        }
+    }
+
+    /// Returns the style of the indicator, if set.
+    pub fn indicator(&self, indicator: Indicator) -> Option<ObjectStyle> {
+        self.indicators.get(&indicator).copied()
+    }
+
+    /// Returns the style of the file name from the suffix entries (`*.tar`), if any.
+    ///
+    /// The longest matching suffix wins, a suffix matching with the same case is preferred to
+    /// one matching with a different case. The last entry wins between the suffixes differing
+    /// only by their case.
+    pub fn style_for_name(&self, name: &str) -> Option<ObjectStyle> {
+        let lowercase_name = name.to_lowercase();
+
+        self.longest_suffix(|suffix| name.ends_with(suffix))
+            .or_else(|| {
+                self.longest_suffix(|suffix| lowercase_name.ends_with(&suffix.to_lowercase()))
+            })
+    }
+
+    /// Returns the style of the file, reading its metadata without following the symbolic
+    /// links. Returns `None` if the file doesn't exist.
+    pub fn style_for_path(&self, path: &Path) -> Option<ObjectStyle> {
+        fs::symlink_metadata(path)
+            .ok()
+            .and_then(|metadata| self.style_for(path, &metadata))
+    }
+
+    /// Returns the style of the target of the symbolic link, like `ls -l` shows it after the
+    /// link (`link -> target`).
+    ///
+    /// Returns the style of `mi` if the target doesn't exist, `None` if the path isn't a
+    /// symbolic link.
+    pub fn style_for_link_target(&self, link: &Path) -> Option<ObjectStyle> {
+        let target = fs::read_link(link).ok()?;
+        // a relative target is relative to the directory of the link
+        let target_path = link
+            .parent()
+            .map_or_else(|| target.clone(), |dir| dir.join(&target));
+
+        match fs::metadata(&target_path) {
+            Ok(metadata) => self.style_for_target(&target, &metadata),
+            Err(_) => self.fallback(Indicator::MissingFile),
+        }
+    }
+
+    /// Returns the style of the file with the given metadata, read without following the
+    /// symbolic links (`fs::symlink_metadata`).
+    ///
+    /// The indicators are checked in the order of `ls`, the suffix entries are only used for the
+    /// regular files without any special property. A missing indicator falls back to `fi` for
+    /// the regular files and to `no` for all the files.
+    pub fn style_for(&self, path: &Path, metadata: &Metadata) -> Option<ObjectStyle> {
+        let file_type = metadata.file_type();
+
+        if file_type.is_symlink() {
+            return match fs::metadata(path) {
+                Ok(target) if self.link_as_target => {
+                    let target_path = fs::read_link(path).unwrap_or_else(|_| path.to_path_buf());
+                    self.style_for_target(&target_path, &target)
+                }
+                Ok(_) => self.fallback(Indicator::SymbolicLink),
+                Err(_) => self
+                    .indicator(Indicator::OrphanedSymbolicLink)
+                    .or_else(|| self.fallback(Indicator::SymbolicLink)),
+            };
+        }
+
+        self.style_for_target(path, metadata)
+    }
+
+    fn style_for_target(&self, path: &Path, metadata: &Metadata) -> Option<ObjectStyle> {
+        if metadata.is_dir() {
+            return self.directory_style(metadata);
+        }
+
+        if let Some(indicator) = special_file_indicator(metadata) {
+            return self.fallback(indicator);
+        }
+
+        for indicator in file_indicators(metadata) {
+            if let Some(style) = self.indicator(indicator) {
+                return Some(style);
+            }
+        }
+
+        path.file_name()
+            .and_then(|name| self.style_for_name(&name.to_string_lossy()))
+            .or_else(|| self.indicator(Indicator::RegularFile))
+            .or_else(|| self.indicator(Indicator::Normal))
+    }
+
+    #[cfg(unix)]
+    fn directory_style(&self, metadata: &Metadata) -> Option<ObjectStyle> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mode = metadata.permissions().mode();
+        let sticky = mode & 0o1000 != 0;
+        let other_writable = mode & 0o002 != 0;
+
+        let indicator = match (sticky, other_writable) {
+            (true, true) => self.indicator(Indicator::StickyOtherWritable),
+            (false, true) => self.indicator(Indicator::OtherWritable),
+            (true, false) => self.indicator(Indicator::Sticky),
+            (false, false) => None,
+        };
+
+        indicator.or_else(|| self.fallback(Indicator::Directory))
+    }
+
+    #[cfg(not(unix))]
+    fn directory_style(&self, _metadata: &Metadata) -> Option<ObjectStyle> {
+        self.fallback(Indicator::Directory)
+    }
+
+    /// Returns the style of the indicator, or the style of `no`.
+    fn fallback(&self, indicator: Indicator) -> Option<ObjectStyle> {
+        self.indicator(indicator)
+            .or_else(|| self.indicator(Indicator::Normal))
+    }
+
+    fn longest_suffix<F: Fn(&str) -> bool>(&self, matches: F) -> Option<ObjectStyle> {
+        self.suffixes
+            .iter()
+            .filter(|(suffix, _)| matches(suffix))
+            .max_by_key(|(suffix, _)| suffix.len())
+            .map(|(_, style)| *style)
+    }
+}
+
+/// Returns the indicator of the pipes, sockets and devices.
+#[cfg(unix)]
+fn special_file_indicator(metadata: &Metadata) -> Option<Indicator> {
+    use std::os::unix::fs::FileTypeExt;
+
+    let file_type = metadata.file_type();
+
+    if file_type.is_fifo() {
+        Some(Indicator::Fifo)
+    } else if file_type.is_socket() {
+        Some(Indicator::Socket)
+    } else if file_type.is_block_device() {
+        Some(Indicator::BlockDevice)
+    } else if file_type.is_char_device() {
+        Some(Indicator::CharacterDevice)
+    } else {
+        None
+    }
+}
+
+#[cfg(not(unix))]
+fn special_file_indicator(_metadata: &Metadata) -> Option<Indicator> {
+    None
+}
+
+/// Returns the indicators of the properties of a regular file, by priority.
+#[cfg(unix)]
+fn file_indicators(metadata: &Metadata) -> Vec<Indicator> {
+    use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+    let mode = metadata.permissions().mode();
+    let mut indicators = Vec::new();
+
+    if mode & 0o4000 != 0 {
+        indicators.push(Indicator::Setuid);
+    }
+    if mode & 0o2000 != 0 {
+        indicators.push(Indicator::Setgid);
+    }
+    if mode & 0o111 != 0 {
+        indicators.push(Indicator::Executable);
+    }
+    if metadata.nlink() > 1 {
+        indicators.push(Indicator::MultipleHardLinks);
+    }
+
+    indicators
+}
+
+#[cfg(not(unix))]
+fn file_indicators(_metadata: &Metadata) -> Vec<Indicator> {
+    Vec::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Attribute, Color, Indicator, LsColors, ObjectStyle};
+
+    fn style(fg_color: Color, bold: bool) -> ObjectStyle {
+        let mut style = ObjectStyle::new().fg(fg_color);
+        if bold {
+            style.add_attr(Attribute::Bold);
+        }
+        style
+    }
+
+    #[test]
+    fn test_parse() {
+        let ls_colors = LsColors::parse("rs=0:di=01;34:ln=38;5;81:xx=1:*.gz=31:bad:ex=zz:*.GZ=32");
+
+        assert_eq!(
+            ls_colors.indicator(Indicator::Directory),
+            Some(style(Color::DarkBlue, true))
+        );
+        assert_eq!(
+            ls_colors.indicator(Indicator::SymbolicLink),
+            Some(ObjectStyle::new().fg(Color::AnsiValue(81)))
+        );
+        assert_eq!(ls_colors.indicator(Indicator::Executable), None);
+        assert_eq!(
+            ls_colors.style_for_name("a.gz"),
+            Some(style(Color::DarkRed, false))
+        );
+        assert_eq!(
+            ls_colors.style_for_name("a.GZ"),
+            Some(style(Color::DarkGreen, false))
+        );
+        assert_eq!(
+            ls_colors.style_for_name("a.Gz"),
+            Some(style(Color::DarkGreen, false))
+        );
+        assert_eq!(ls_colors.style_for_name("gz"), None);
+    }
+
+    #[test]
+    fn test_longest_suffix() {
+        let ls_colors = LsColors::parse("*.tar.gz=31:*.gz=32:*README=33");
+
+        assert_eq!(
+            ls_colors.style_for_name("a.tar.gz").unwrap().fg_color,
+            Some(Color::DarkRed)
+        );
+        assert_eq!(
+            ls_colors.style_for_name("a.gz").unwrap().fg_color,
+            Some(Color::DarkGreen)
+        );
+        assert_eq!(
+            ls_colors.style_for_name("README").unwrap().fg_color,
+            Some(Color::DarkYellow)
+        );
+    }
+
+    #[test]
+    fn test_indicator_codes() {
+        assert_eq!(
+            Indicator::from_code("tw"),
+            Some(Indicator::StickyOtherWritable)
+        );
+        assert_eq!(Indicator::from_code("zz"), None);
+        assert_eq!(Indicator::Executable.code(), "ex");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_style_for_path() {
+        use std::fs;
+        use std::os::unix::fs::{symlink, PermissionsExt};
+
+        let dir = std::env::temp_dir().join(format!("crossterm_style_ls_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let file = dir.join("notes.txt");
+        let exe = dir.join("run.txt");
+        let archive = dir.join("backup.tar");
+        let link = dir.join("link");
+        let orphan = dir.join("orphan");
+
+        fs::write(&file, "").unwrap();
+        fs::write(&exe, "").unwrap();
+        fs::write(&archive, "").unwrap();
+        fs::set_permissions(&exe, fs::Permissions::from_mode(0o755)).unwrap();
+        symlink(&archive, &link).unwrap();
+        symlink(dir.join("missing"), &orphan).unwrap();
+
+        let ls_colors = LsColors::parse("no=37:di=34:ln=36:or=31:mi=35:ex=32:*.tar=33");
+
+        let fg = |path: &std::path::Path| ls_colors.style_for_path(path).unwrap().fg_color;
+
+        assert_eq!(fg(&dir), Some(Color::DarkBlue));
+        assert_eq!(fg(&file), Some(Color::Grey));
+        assert_eq!(fg(&exe), Some(Color::DarkGreen));
+        assert_eq!(fg(&archive), Some(Color::DarkYellow));
+        assert_eq!(fg(&link), Some(Color::DarkCyan));
+        assert_eq!(fg(&orphan), Some(Color::DarkRed));
+        assert_eq!(ls_colors.style_for_path(&dir.join("missing")), None);
+
+        let target_fg = |path: &std::path::Path| {
+            ls_colors
+                .style_for_link_target(path)
+                .and_then(|style| style.fg_color)
+        };
+
+        assert_eq!(target_fg(&link), Some(Color::DarkYellow));
+        assert_eq!(target_fg(&orphan), Some(Color::DarkMagenta));
+        assert_eq!(target_fg(&file), None);
+
+        let ls_colors = LsColors::parse("ln=target:*.tar=33");
+        assert_eq!(
+            ls_colors.style_for_path(&link).unwrap().fg_color,
+            Some(Color::DarkYellow)
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -22,25 +22,30 @@ macro_rules! def_color {
 
 macro_rules! def_str_color {
     ($side:ident: $name:ident => $color:path) => {
-        fn $name(self) -> StyledObject< &'static str> {
+        fn $name(self) -> StyledObject<&'static str> {
             StyledObject {
                 object_style: ObjectStyle {
                     $side: Some($color),
                     ..Default::default()
                 },
-                content: self
+                content: self,
+                padding: Default::default(),
             }
         }
     };
 }
 
 macro_rules! def_str_attr {
-    ($name:ident => $color:path) => {
+    ($name:ident => $attr:path) => {
         fn $name(self) -> StyledObject<&'static str> {
             StyledObject {
-                object_style: Default::default(),
+                object_style: ObjectStyle {
+                    attrs: $attr.into(),
+                    ..Default::default()
+                },
                 content: self,
+                padding: Default::default(),
             }
         }
-    }
+    };
 }
@@ -1,26 +1,53 @@
 //! This module contains the `object style` that can be applied to an `styled object`.
 
-use std::fmt::Display;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
 
-use super::{Attribute, Color, StyledObject};
+use crate::enums::{format_color, ATTRIBUTE_NAMES, COLOR_NAMES};
+use crate::error::suggest;
+
+use super::{Attribute, Attributes, Color, ParseStyleError, ParseStyleErrorKind, StyledObject};
 
 /// An object style.
-#[derive(Debug, Clone, Default)]
+///
+/// The style is `Copy`, it's cheap to pass it around.
+///
+/// # String Representation
+///
+/// The style can be parsed from and written to a string containing the attributes, the
+/// foreground color and the background color prefixed with `on`, separated by whitespace.
+/// A second foreground or background color is an error.
+/// See [`Attribute::from_str`](enum.Attribute.html#method.from_str) and
+/// [`Color::from_str`](enum.Color.html#method.from_str) for the supported names.
+///
+/// ```
+/// use crossterm_style::{Attribute, Color, ObjectStyle};
+///
+/// let style: ObjectStyle = "bold underline #ff8800 on dark_blue".parse().unwrap();
+///
+/// assert_eq!(style.fg_color, Some(Color::Rgb { r: 255, g: 136, b: 0 }));
+/// assert_eq!(style.bg_color, Some(Color::DarkBlue));
+/// assert_eq!(style.attrs, Attribute::Bold | Attribute::Underlined);
+///
+/// assert_eq!(style.to_string(), "bold underlined #ff8800 on dark_blue");
+/// ```
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
 pub struct ObjectStyle {
     /// The foreground color.
     pub fg_color: Option<Color>,
     /// The background color.
     pub bg_color: Option<Color>,
-    /// List of attributes.
-    pub attrs: Vec<Attribute>,
+    /// Set of attributes.
+    pub attrs: Attributes,
 }
 
 impl ObjectStyle {
     /// Creates a `StyledObject` by applying the style to the given `val`.
     pub fn apply_to<D: Display + Clone>(&self, val: D) -> StyledObject<D> {
         StyledObject {
-            object_style: self.clone(),
+            object_style: *self,
             content: val,
+            padding: Default::default(),
         }
     }
 
@@ -41,17 +68,179 @@ impl ObjectStyle {
         self
     }
 
+    /// Returns `true` if the style has no colors and no attributes.
+    pub fn is_empty(&self) -> bool {
+        self.fg_color.is_none() && self.bg_color.is_none() && self.attrs.is_empty()
+    }
+
+    /// Returns the style with the colors and attributes of `other` applied on top of it.
+    ///
+    /// The colors of `other` replace the ones of this style and the attributes are combined
+    /// (see [`Attributes::union`](struct.Attributes.html#method.union)). If `other` contains
+    /// `Attribute::Reset`, the result is `other`.
+    pub fn merge(self, other: ObjectStyle) -> ObjectStyle {
+        if other.attrs.contains(Attribute::Reset) {
+            return other;
+        }
+
+        ObjectStyle {
+            fg_color: other.fg_color.or(self.fg_color),
+            bg_color: other.bg_color.or(self.bg_color),
+            attrs: self.attrs.union(other.attrs),
+        }
+    }
+
     /// Adds the attribute.
     ///
-    /// You can add more attributes by calling this method multiple times.
+    /// You can add more attributes by calling this method multiple times. See
+    /// [`Attributes::insert`](struct.Attributes.html#method.insert) for more info.
     pub fn add_attr(&mut self, attr: Attribute) {
-        self.attrs.push(attr);
+        self.attrs.insert(attr);
+    }
+}
+
+impl FromStr for ObjectStyle {
+    type Err = ParseStyleError;
+
+    /// Creates an `ObjectStyle` from the string representation.
+    ///
+    /// See [String Representation](struct.ObjectStyle.html#string-representation) for more info.
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        let mut style = ObjectStyle::new();
+        let mut tokens = tokenize(src).into_iter();
+
+        while let Some((position, token)) = tokens.next() {
+            if token.eq_ignore_ascii_case("on") {
+                let (position, token) = match tokens.next() {
+                    Some(color_token) => color_token,
+                    None => {
+                        return Err(
+                            ParseStyleError::new(&token, ParseStyleErrorKind::MissingColor)
+                                .at(position),
+                        )
+                    }
+                };
+                let color = parse_color(&token, &mut tokens, position)?;
+                set_color(&mut style.bg_color, color, &token, position)?;
+                continue;
+            }
+
+            if let Ok(attr) = token.parse::<Attribute>() {
+                style.attrs.insert(attr);
+                continue;
+            }
+
+            let color = parse_color(&token, &mut tokens, position)?;
+            set_color(&mut style.fg_color, color, &token, position)?;
+        }
+
+        Ok(style)
+    }
+}
+
+/// Sets the foreground or background color, fails if it's already set.
+fn set_color(
+    slot: &mut Option<Color>,
+    color: Color,
+    token: &str,
+    position: usize,
+) -> Result<(), ParseStyleError> {
+    if slot.is_some() {
+        return Err(ParseStyleError::new(token, ParseStyleErrorKind::TooManyColors).at(position));
+    }
+
+    *slot = Some(color);
+    Ok(())
+}
+
+impl Display for ObjectStyle {
+    /// Writes the string representation of the style.
+    ///
+    /// See [String Representation](struct.ObjectStyle.html#string-representation) for more info.
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let mut words: Vec<String> = self
+            .attrs
+            .iter()
+            .map(|attr| attr.name().to_string())
+            .collect();
+
+        if let Some(fg) = self.fg_color {
+            words.push(format_color(fg));
+        }
+
+        if let Some(bg) = self.bg_color {
+            words.push(format!("on {}", format_color(bg)));
+        }
+
+        write!(f, "{}", words.join(" "))
+    }
+}
+
+/// Splits the input into whitespace separated tokens with their positions.
+///
+/// Whitespace inside parentheses doesn't split, so that `rgb(1, 2, 3)` is a single token.
+fn tokenize(src: &str) -> Vec<(usize, String)> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+    let mut depth = 0;
+
+    for (index, c) in src.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ if c.is_whitespace() && depth <= 0 => {
+                if let Some(start) = start.take() {
+                    tokens.push((start, src[start..index].to_string()));
+                }
+                continue;
+            }
+            _ => {}
+        }
+
+        if start.is_none() {
+            start = Some(index);
+        }
+    }
+
+    if let Some(start) = start {
+        tokens.push((start, src[start..].to_string()));
+    }
+
+    tokens
+}
+
+/// Parses the color token, the `bright` token is combined with the following one.
+fn parse_color<I>(token: &str, tokens: &mut I, position: usize) -> Result<Color, ParseStyleError>
+where
+    I: Iterator<Item = (usize, String)>,
+{
+    if token.eq_ignore_ascii_case("bright") {
+        if let Some((_, next)) = tokens.next() {
+            return format!("{} {}", token, next)
+                .parse::<Color>()
+                .map_err(|error| error.offset(position));
+        }
     }
+
+    token.parse::<Color>().map_err(|error| {
+        if error.kind() != ParseStyleErrorKind::UnknownColor {
+            return error.offset(position);
+        }
+
+        let names = COLOR_NAMES
+            .iter()
+            .map(|(name, _)| *name)
+            .chain(ATTRIBUTE_NAMES.iter().map(|(name, _)| *name));
+
+        ParseStyleError::new(token, ParseStyleErrorKind::UnknownName)
+            .at(position)
+            .with_suggestion(suggest(token, names))
+    })
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{Attribute, Color, ObjectStyle};
+    use crate::{Attribute, Color, ObjectStyle, ParseStyleErrorKind};
 
     #[test]
     fn test_set_fg_bg_add_attr() {
@@ -60,7 +249,7 @@ mod tests {
 
         assert_eq!(object_style.fg_color, Some(Color::Blue));
         assert_eq!(object_style.bg_color, Some(Color::Red));
-        assert_eq!(object_style.attrs[0], Attribute::Reset);
+        assert!(object_style.attrs.contains(Attribute::Reset));
     }
 
     #[test]
@@ -72,6 +261,100 @@ mod tests {
 
         assert_eq!(styled_object.object_style.fg_color, Some(Color::Blue));
         assert_eq!(styled_object.object_style.bg_color, Some(Color::Red));
-        assert_eq!(styled_object.object_style.attrs[0], Attribute::Reset);
+        assert!(styled_object.object_style.attrs.contains(Attribute::Reset));
+    }
+
+    #[test]
+    fn test_merge_object_styles() {
+        let base = ObjectStyle::new().fg(Color::Blue).bg(Color::Red);
+        let mut other = ObjectStyle::new().fg(Color::Green);
+        other.add_attr(Attribute::Bold);
+
+        let merged = base.merge(other);
+        assert_eq!(merged.fg_color, Some(Color::Green));
+        assert_eq!(merged.bg_color, Some(Color::Red));
+        assert_eq!(merged.attrs, Attribute::Bold.into());
+
+        other.add_attr(Attribute::Reset);
+        assert_eq!(base.merge(other), other);
+    }
+
+    #[test]
+    fn test_parse_object_style() {
+        let style: ObjectStyle = "bold underline #ff8800 on dark_blue".parse().unwrap();
+
+        assert_eq!(
+            style.fg_color,
+            Some(Color::Rgb {
+                r: 255,
+                g: 136,
+                b: 0
+            })
+        );
+        assert_eq!(style.bg_color, Some(Color::DarkBlue));
+        assert_eq!(style.attrs, Attribute::Bold | Attribute::Underlined);
+    }
+
+    #[test]
+    fn test_parse_object_style_with_spaces_in_colors() {
+        let style: ObjectStyle = "bright red on rgb(1, 2, 3)".parse().unwrap();
+
+        assert_eq!(style.fg_color, Some(Color::Red));
+        assert_eq!(style.bg_color, Some(Color::Rgb { r: 1, g: 2, b: 3 }));
+        assert!(style.attrs.is_empty());
+    }
+
+    #[test]
+    fn test_parse_empty_object_style() {
+        assert_eq!("".parse(), Ok(ObjectStyle::new()));
+        assert_eq!("on red".parse(), Ok(ObjectStyle::new().bg(Color::Red)));
+    }
+
+    #[test]
+    fn test_parse_object_style_errors() {
+        let error = "bold redd".parse::<ObjectStyle>().unwrap_err();
+        assert_eq!(error.kind(), ParseStyleErrorKind::UnknownName);
+        assert_eq!(error.position(), 5);
+        assert_eq!(error.suggestion(), Some("red"));
+
+        let error = "italc red".parse::<ObjectStyle>().unwrap_err();
+        assert_eq!(error.suggestion(), Some("italic"));
+
+        let error = "red on".parse::<ObjectStyle>().unwrap_err();
+        assert_eq!(error.kind(), ParseStyleErrorKind::MissingColor);
+        assert_eq!(error.position(), 4);
+
+        let error = "red on #12".parse::<ObjectStyle>().unwrap_err();
+        assert_eq!(error.kind(), ParseStyleErrorKind::InvalidHex);
+        assert_eq!(error.position(), 7);
+
+        let error = "red bold blue".parse::<ObjectStyle>().unwrap_err();
+        assert_eq!(error.kind(), ParseStyleErrorKind::TooManyColors);
+        assert_eq!(error.position(), 9);
+
+        let error = "on red  on blue".parse::<ObjectStyle>().unwrap_err();
+        assert_eq!(error.kind(), ParseStyleErrorKind::TooManyColors);
+        assert_eq!(error.position(), 11);
+    }
+
+    #[test]
+    fn test_object_style_round_trip() {
+        let mut style = ObjectStyle::new()
+            .fg(Color::AnsiValue(208))
+            .bg(Color::Reset);
+        style.add_attr(Attribute::Reset);
+        style.add_attr(Attribute::CrossedOut);
+        style.add_attr(Attribute::NoItalic);
+
+        assert_eq!(
+            style.to_string(),
+            "reset crossed_out no_italic ansi(208) on default"
+        );
+        assert_eq!(style.to_string().parse(), Ok(style));
+
+        for (_, color) in crate::enums::COLOR_NAMES.iter() {
+            let style = ObjectStyle::new().fg(*color).bg(*color);
+            assert_eq!(style.to_string().parse(), Ok(style));
+        }
     }
 }
@@ -0,0 +1,108 @@
+//! This module contains the terminal color palettes used to turn the colors into RGB values.
+
+use crate::depth::NAMED_COLORS_RGB;
+use crate::Color;
+
+/// A terminal color palette.
+///
+/// The palette gives the RGB values of the 16 named colors and of the default foreground and
+/// background colors, which depend on the terminal. The other colors (the 6x6x6 color cube and
+/// the grayscale ramp of the 256 colors palette) are the same on all the terminals.
+///
+/// # Examples
+///
+/// ```
+/// use crossterm_style::{Color, Palette};
+///
+/// let mut palette = Palette::xterm();
+/// palette.named[1] = (204, 36, 29);
+///
+/// assert_eq!(palette.rgb(Color::DarkRed), Some((204, 36, 29)));
+/// assert_eq!(palette.rgb(Color::AnsiValue(1)), Some((204, 36, 29)));
+/// assert_eq!(palette.rgb(Color::Reset), None);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Palette {
+    /// The RGB values of the named colors, ordered by their ANSI index (`Black`, `DarkRed`,
+    /// `DarkGreen`, `DarkYellow`, `DarkBlue`, `DarkMagenta`, `DarkCyan`, `Grey`, `DarkGrey`,
+    /// `Red`, `Green`, `Yellow`, `Blue`, `Magenta`, `Cyan`, `White`).
+    pub named: [(u8, u8, u8); 16],
+    /// The RGB value of the default foreground color.
+    pub foreground: (u8, u8, u8),
+    /// The RGB value of the default background color.
+    pub background: (u8, u8, u8),
+}
+
+impl Palette {
+    /// Returns the default xterm palette, light grey on black.
+    pub fn xterm() -> Palette {
+        Palette {
+            named: NAMED_COLORS_RGB,
+            foreground: (229, 229, 229),
+            background: (0, 0, 0),
+        }
+    }
+
+    /// Returns the VGA palette used by the Linux console, light grey on black.
+    pub fn vga() -> Palette {
+        Palette {
+            named: [
+                (0, 0, 0),
+                (170, 0, 0),
+                (0, 170, 0),
+                (170, 85, 0),
+                (0, 0, 170),
+                (170, 0, 170),
+                (0, 170, 170),
+                (170, 170, 170),
+                (85, 85, 85),
+                (255, 85, 85),
+                (85, 255, 85),
+                (255, 255, 85),
+                (85, 85, 255),
+                (255, 85, 255),
+                (85, 255, 255),
+                (255, 255, 255),
+            ],
+            foreground: (170, 170, 170),
+            background: (0, 0, 0),
+        }
+    }
+
+    /// Returns the RGB value of the color, `None` for `Color::Reset` (the default color).
+    pub fn rgb(&self, color: Color) -> Option<(u8, u8, u8)> {
+        match color {
+            Color::Reset => None,
+            Color::AnsiValue(val) if val < 16 => Some(self.named[val as usize]),
+            _ => match color.ansi_index() {
+                Some(index) => Some(self.named[index as usize]),
+                None => color.to_rgb(),
+            },
+        }
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Palette::xterm()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Color, Palette};
+
+    #[test]
+    fn test_rgb() {
+        let palette = Palette::vga();
+
+        assert_eq!(palette.rgb(Color::DarkYellow), Some((170, 85, 0)));
+        assert_eq!(palette.rgb(Color::AnsiValue(9)), Some((255, 85, 85)));
+        assert_eq!(palette.rgb(Color::AnsiValue(16)), Some((0, 0, 0)));
+        assert_eq!(palette.rgb(Color::AnsiValue(255)), Some((238, 238, 238)));
+        assert_eq!(
+            palette.rgb(Color::Rgb { r: 1, g: 2, b: 3 }),
+            Some((1, 2, 3))
+        );
+    }
+}
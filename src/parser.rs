@@ -0,0 +1,357 @@
+//! This module contains the parser reading the styled text back from ANSI escape sequences.
+
+use std::borrow::Cow;
+
+use crate::style::ansi::apply_sgr_params;
+use crate::ObjectStyle;
+
+const ESC: char = '\x1B';
+/// The 8-bit `CSI` control character.
+const C1_CSI: char = '\u{9B}';
+const BEL: char = '\x07';
+
+/// Represents a piece of the input.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Token<'a> {
+    /// Plain text.
+    Text(&'a str),
+    /// The parameters of an SGR sequence (`CSI ... m`).
+    Sgr(&'a str),
+    /// Any other escape sequence, including the malformed ones.
+    Escape(&'a str),
+}
+
+/// Splits a string into text and escape sequences.
+#[derive(Clone, Debug)]
+pub(crate) struct Tokens<'a> {
+    input: &'a str,
+}
+
+impl<'a> Tokens<'a> {
+    pub(crate) fn new(input: &'a str) -> Tokens<'a> {
+        Tokens { input }
+    }
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.input.is_empty() {
+            return None;
+        }
+
+        let text_len = self.input.find([ESC, C1_CSI]).unwrap_or(self.input.len());
+
+        if text_len > 0 {
+            let (text, rest) = self.input.split_at(text_len);
+            self.input = rest;
+            return Some(Token::Text(text));
+        }
+
+        // the sequence cut at the end of the input goes up to the end
+        let (token, len) =
+            escape_sequence(self.input).unwrap_or((Token::Escape(self.input), self.input.len()));
+        self.input = &self.input[len..];
+        Some(token)
+    }
+}
+
+/// Splits the input before the escape sequence which is cut at the end of the input, if any.
+///
+/// The second part must be read again with the rest of the input, once it's available.
+pub(crate) fn split_incomplete(input: &str) -> (&str, &str) {
+    let mut offset = 0;
+
+    while let Some(start) = input[offset..].find([ESC, C1_CSI]) {
+        let start = offset + start;
+
+        match escape_sequence(&input[start..]) {
+            Some((_, len)) => offset = start + len,
+            None => return input.split_at(start),
+        }
+    }
+
+    (input, "")
+}
+
+/// Reads the escape sequence at the start of the input, returns it with its length.
+///
+/// Returns `None` if the sequence is cut at the end of the input.
+fn escape_sequence(input: &str) -> Option<(Token<'_>, usize)> {
+    let mut chars = input.char_indices();
+
+    let introducer = match chars.next() {
+        Some((_, C1_CSI)) => '[',
+        _ => chars.next()?.1,
+    };
+
+    let escape = |len: usize| Some((Token::Escape(&input[..len]), len));
+
+    match introducer {
+        '[' => {
+            let params_start = chars.offset();
+
+            for (index, c) in chars {
+                match c {
+                    '\x20'..='\x3F' => {}
+                    '\x40'..='\x7E' => {
+                        let params = &input[params_start..index];
+                        let len = index + 1;
+
+                        let is_sgr = c == 'm'
+                            && params
+                                .chars()
+                                .all(|c| c.is_ascii_digit() || c == ';' || c == ':');
+
+                        return if is_sgr {
+                            Some((Token::Sgr(params), len))
+                        } else {
+                            escape(len)
+                        };
+                    }
+                    // malformed sequence, ends before the unexpected character
+                    _ => return escape(index),
+                }
+            }
+
+            None
+        }
+        // OSC, DCS, SOS, PM and APC strings, terminated by `BEL` or `ESC \`
+        ']' | 'P' | 'X' | '^' | '_' => {
+            let mut previous = None;
+
+            for (index, c) in chars {
+                if c == BEL || (previous == Some(ESC) && c == '\\') {
+                    return escape(index + c.len_utf8());
+                }
+                previous = Some(c);
+            }
+
+            None
+        }
+        // `ESC` followed by intermediate bytes and a final byte
+        _ => {
+            let mut len = 1;
+
+            for c in input[1..].chars() {
+                len += c.len_utf8();
+
+                if !('\x20'..='\x2F').contains(&c) {
+                    if !('\x30'..='\x7E').contains(&c) {
+                        // malformed sequence, drops the `ESC` only
+                        return escape(1);
+                    }
+                    return escape(len);
+                }
+            }
+
+            None
+        }
+    }
+}
+
+/// An iterator over the styled text of a string containing ANSI escape sequences.
+///
+/// Each item is the style (the state of the terminal) and the text written with it. The SGR
+/// sequences (`CSI ... m`) change the style, all the other escape sequences are skipped.
+/// Malformed and unknown sequences are skipped as well.
+///
+/// See [`parse_ansi`](fn.parse_ansi.html) for more info.
+#[derive(Clone, Debug)]
+pub struct AnsiSpans<'a> {
+    tokens: Tokens<'a>,
+    style: ObjectStyle,
+}
+
+impl<'a> AnsiSpans<'a> {
+    /// Creates a new `AnsiSpans` iterator over the input.
+    pub fn new(input: &'a str) -> AnsiSpans<'a> {
+        AnsiSpans {
+            tokens: Tokens::new(input),
+            style: ObjectStyle::new(),
+        }
+    }
+}
+
+impl<'a> Iterator for AnsiSpans<'a> {
+    type Item = (ObjectStyle, &'a str);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for token in &mut self.tokens {
+            match token {
+                Token::Text(text) => return Some((self.style, text)),
+                Token::Sgr(params) => apply_sgr_params(&mut self.style, params),
+                Token::Escape(_) => {}
+            }
+        }
+
+        None
+    }
+}
+
+/// Parses the styled text of the input containing ANSI escape sequences.
+///
+/// Returns the styled spans, the adjacent spans with the same style are merged. The SGR
+/// sequences with the extended colors (`38;5;n`, `38;2;r;g;b` and the colon separated
+/// `38:5:n`, `38:2::r:g:b` forms) are supported. Invalid UTF-8 sequences are replaced with
+/// `U+FFFD`.
+///
+/// # Examples
+///
+/// ```
+/// use crossterm_style::{parse_ansi, Attribute, Color, ObjectStyle};
+///
+/// let spans = parse_ansi("\x1B[1;31mError:\x1B[0m file not found");
+///
+/// let mut error = ObjectStyle::new().fg(Color::DarkRed);
+/// error.add_attr(Attribute::Bold);
+///
+/// assert_eq!(
+///     spans,
+///     vec![
+///         (error, "Error:".to_string()),
+///         (ObjectStyle::new(), " file not found".to_string())
+///     ]
+/// );
+/// ```
+pub fn parse_ansi<T: AsRef<[u8]>>(input: T) -> Vec<(ObjectStyle, String)> {
+    let input: Cow<str> = String::from_utf8_lossy(input.as_ref());
+    let mut spans: Vec<(ObjectStyle, String)> = Vec::new();
+
+    for (style, text) in AnsiSpans::new(&input) {
+        match spans.last_mut() {
+            Some((last_style, last_text)) if *last_style == style => last_text.push_str(text),
+            _ => spans.push((style, text.to_string())),
+        }
+    }
+
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse_ansi, AnsiSpans, Attribute, Color, ObjectStyle};
+
+    use super::{split_incomplete, Token, Tokens};
+
+    #[test]
+    fn test_tokens() {
+        let tokens: Vec<Token> =
+            Tokens::new("a\x1B[1;2mb\x1B[2Jc\x1B]0;title\x07d\x1B7e").collect();
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Text("a"),
+                Token::Sgr("1;2"),
+                Token::Text("b"),
+                Token::Escape("\x1B[2J"),
+                Token::Text("c"),
+                Token::Escape("\x1B]0;title\x07"),
+                Token::Text("d"),
+                Token::Escape("\x1B7"),
+                Token::Text("e"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_malformed_tokens() {
+        let tokens: Vec<Token> = Tokens::new("\x1B[1\nb\x1B\x01c\x1B[?25hd\x1B[1").collect();
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Escape("\x1B[1"),
+                Token::Text("\nb"),
+                Token::Escape("\x1B"),
+                Token::Text("\x01c"),
+                Token::Escape("\x1B[?25h"),
+                Token::Text("d"),
+                Token::Escape("\x1B[1"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_split_incomplete() {
+        assert_eq!(split_incomplete("a\x1B[1mb"), ("a\x1B[1mb", ""));
+        assert_eq!(split_incomplete("a\x1B[1;3"), ("a", "\x1B[1;3"));
+        assert_eq!(split_incomplete("a\x1B"), ("a", "\x1B"));
+        assert_eq!(split_incomplete("a\u{9B}"), ("a", "\u{9B}"));
+        assert_eq!(split_incomplete("\x1B]0;t\x1B"), ("", "\x1B]0;t\x1B"));
+        assert_eq!(split_incomplete("\x1B(\x1B(B"), ("\x1B(\x1B(B", ""));
+        assert_eq!(split_incomplete("\x1B[1\n\x1B("), ("\x1B[1\n", "\x1B("));
+    }
+
+    #[test]
+    fn test_spans() {
+        let spans: Vec<_> = AnsiSpans::new("a\x1B[38;5;9mb\x1B[Kc\x1B[mde").collect();
+        let red = ObjectStyle::new().fg(Color::Red);
+
+        assert_eq!(
+            spans,
+            vec![
+                (ObjectStyle::new(), "a"),
+                (red, "b"),
+                (red, "c"),
+                (ObjectStyle::new(), "de")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_merges_spans() {
+        let spans = parse_ansi("\x1B[4ma\x1B[Kb\x1B[24m\x1B[49mc".as_bytes());
+
+        assert_eq!(
+            spans,
+            vec![
+                (ObjectStyle::new().merge(underlined()), "ab".to_string()),
+                (ObjectStyle::new(), "c".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_styled_object_output() {
+        crate::set_color_choice(crate::ColorChoice::Always);
+
+        let style = ObjectStyle::new()
+            .fg(Color::Rgb { r: 1, g: 2, b: 3 })
+            .bg(Color::DarkBlue)
+            .merge(underlined());
+
+        let rendered = format!("x{}y", style.apply_to("styled"));
+
+        assert_eq!(
+            parse_ansi(rendered),
+            vec![
+                (ObjectStyle::new(), "x".to_string()),
+                (style, "styled".to_string()),
+                (ObjectStyle::new(), "y".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_utf8() {
+        assert_eq!(
+            parse_ansi(b"\x1B[1ma\xFF"),
+            vec![(bold(), "a\u{FFFD}".to_string())]
+        );
+    }
+
+    fn underlined() -> ObjectStyle {
+        let mut style = ObjectStyle::new();
+        style.add_attr(Attribute::Underlined);
+        style
+    }
+
+    fn bold() -> ObjectStyle {
+        let mut style = ObjectStyle::new();
+        style.add_attr(Attribute::Bold);
+        style
+    }
+}
@@ -0,0 +1,394 @@
+//! This module contains the color policy deciding whether the styles are written or not.
+
+use std::cell::Cell;
+use std::env;
+use std::fmt;
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use crate::filter::EscapeFilter;
+use crate::support::is_stdout_tty;
+use crate::ColorSupport;
+
+/// Represents when to write colors and attributes.
+///
+/// # Examples
+///
+/// Implement a `--color=<when>` flag:
+///
+/// ```no_run
+/// use crossterm_style::{set_color_choice, ColorChoice, Colorize};
+///
+/// let when = "never";
+///
+/// set_color_choice(match when {
+///     "always" => ColorChoice::Always,
+///     "never" => ColorChoice::Never,
+///     _ => ColorChoice::Auto,
+/// });
+///
+/// // Prints a plain `Error`
+/// println!("{}", "Error".red());
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ColorChoice {
+    /// Always write the styles.
+    Always,
+    /// Never write the styles.
+    Never,
+    /// Write the styles if the environment and the output allow it.
+    ///
+    /// See [`ColorChoice::should_colorize`](enum.ColorChoice.html#method.should_colorize) for
+    /// more info.
+    Auto,
+}
+
+impl ColorChoice {
+    /// Returns `true` if the styles should be written to a stream.
+    ///
+    /// `is_tty` tells if the stream is a terminal. `ColorChoice::Auto` respects the following
+    /// environment variables (in this order):
+    ///
+    /// * `FORCE_COLOR` - enables the styles, unless set to `0` or `false` which disables them.
+    /// * `CLICOLOR_FORCE` - enables the styles, unless set to `0`.
+    /// * `NO_COLOR` - disables the styles if set to a non-empty value.
+    /// * `CLICOLOR` - disables the styles if set to `0`.
+    ///
+    /// Otherwise the styles are written if the stream supports colors
+    /// (see [`ColorSupport::from_env`](enum.ColorSupport.html#method.from_env)). A terminal
+    /// without the `TERM` variable is assumed to be the Windows console, which supports colors
+    /// on Windows.
+    pub fn should_colorize(self, is_tty: bool) -> bool {
+        self.should_colorize_with_env(|key| env::var(key).ok(), is_tty)
+    }
+
+    /// Returns `true` if the styles should be written to a stream.
+    ///
+    /// Same as [`should_colorize`](#method.should_colorize) except that the environment
+    /// variables are read with the given `var` function.
+    pub fn should_colorize_with_env<F>(self, var: F, is_tty: bool) -> bool
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                if let Some(force) = var("FORCE_COLOR") {
+                    return force != "0" && force != "false";
+                }
+
+                if var("CLICOLOR_FORCE").is_some_and(|force| force != "0") {
+                    return true;
+                }
+
+                if var("NO_COLOR").is_some_and(|value| !value.is_empty())
+                    || var("CLICOLOR").is_some_and(|value| value == "0")
+                {
+                    return false;
+                }
+
+                ColorSupport::detect_with_env(var, is_tty) != ColorSupport::None
+            }
+        }
+    }
+}
+
+const UNRESOLVED: u8 = 0;
+const DISABLED: u8 = 1;
+const ENABLED: u8 = 2;
+
+/// The global color choice.
+static COLOR_CHOICE: AtomicU8 = AtomicU8::new(ColorChoice::Auto as u8);
+/// The cached result of `should_colorize` for the global color choice.
+static SHOULD_COLORIZE: AtomicU8 = AtomicU8::new(UNRESOLVED);
+
+thread_local! {
+    /// The color policy of the `ColorWriter` being written to, it overrides the global one.
+    static SCOPED_COLORIZE: Cell<Option<bool>> = const { Cell::new(None) };
+}
+
+/// Sets the global color choice.
+///
+/// The global color choice is checked by the `SetFg`, `SetBg`, `SetAttr`, `ResetColor` commands
+/// and by the `StyledObject` `Display` implementation. The default value is
+/// `ColorChoice::Auto`, which is resolved for the standard output. The color choice of a
+/// [`ColorWriter`](struct.ColorWriter.html) overrides it while writing to that writer.
+pub fn set_color_choice(choice: ColorChoice) {
+    COLOR_CHOICE.store(choice as u8, Ordering::Relaxed);
+    SHOULD_COLORIZE.store(UNRESOLVED, Ordering::Relaxed);
+}
+
+/// Returns the global color choice.
+///
+/// See [`set_color_choice`](fn.set_color_choice.html) for more info.
+pub fn color_choice() -> ColorChoice {
+    match COLOR_CHOICE.load(Ordering::Relaxed) {
+        0 => ColorChoice::Always,
+        1 => ColorChoice::Never,
+        _ => ColorChoice::Auto,
+    }
+}
+
+/// Returns `true` if the styles should be written according to the global color choice.
+///
+/// The color choice of the [`ColorWriter`](struct.ColorWriter.html) being written to is used
+/// instead of the global one.
+pub fn should_colorize() -> bool {
+    if let Some(colorize) = SCOPED_COLORIZE.with(Cell::get) {
+        return colorize;
+    }
+
+    match SHOULD_COLORIZE.load(Ordering::Relaxed) {
+        DISABLED => false,
+        ENABLED => true,
+        _ => {
+            let colorize = color_choice().should_colorize(is_stdout_tty());
+            SHOULD_COLORIZE.store(if colorize { ENABLED } else { DISABLED }, Ordering::Relaxed);
+            colorize
+        }
+    }
+}
+
+/// Runs the function with the given policy overriding the global color choice on this thread.
+fn with_colorize<R, F: FnOnce() -> R>(colorize: bool, f: F) -> R {
+    /// Restores the previous policy, even if the function panics.
+    struct Restore(Option<bool>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            SCOPED_COLORIZE.with(|scoped| scoped.set(self.0));
+        }
+    }
+
+    let _restore = Restore(SCOPED_COLORIZE.with(|scoped| scoped.replace(Some(colorize))));
+    f()
+}
+
+/// A writer with its own color policy.
+///
+/// The policy of the writer overrides the global color choice while formatting with `write!`:
+/// the `StyledObject`s and the commands are written according to the writer policy, even if
+/// the global color choice disables the styles. The SGR escape sequences (colors and
+/// attributes) written to this writer are also removed when the policy disables the styles.
+/// All the other escape sequences are left untouched.
+///
+/// The `queue!` macro creates the escape sequences before writing them, it must run in
+/// [`scope`](#method.scope) to follow the writer policy.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::fs::File;
+/// use std::io::Write;
+///
+/// use crossterm_style::{ColorChoice, ColorWriter, Colorize};
+///
+/// # fn main() -> std::io::Result<()> {
+/// let mut log = ColorWriter::new(File::create("log.txt")?, ColorChoice::Auto, false);
+///
+/// // Writes a plain `Error` unless the colors are forced.
+/// write!(log, "{}", "Error".red())?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct ColorWriter<W: Write> {
+    writer: W,
+    filter: Option<EscapeFilter>,
+}
+
+impl<W: Write> ColorWriter<W> {
+    /// Creates a new `ColorWriter`.
+    ///
+    /// `is_tty` tells if the writer is a terminal, it's used to resolve `ColorChoice::Auto`.
+    pub fn new(writer: W, choice: ColorChoice, is_tty: bool) -> ColorWriter<W> {
+        let filter = if choice.should_colorize(is_tty) {
+            None
+        } else {
+            Some(EscapeFilter::sgr())
+        };
+
+        ColorWriter { writer, filter }
+    }
+
+    /// Returns `true` if the styles are written.
+    pub fn is_colorized(&self) -> bool {
+        self.filter.is_none()
+    }
+
+    /// Runs the function with the policy of this writer overriding the global color choice.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::fs::File;
+    /// use std::io::Write;
+    ///
+    /// use crossterm_style::{queue, Color, ColorChoice, ColorWriter, SetFg};
+    ///
+    /// # fn main() -> crossterm_style::Result<()> {
+    /// let mut log = ColorWriter::new(File::create("log.txt")?, ColorChoice::Always, false);
+    ///
+    /// // Writes the sequence even if the standard output is not a terminal.
+    /// log.scope(|log| queue!(log, SetFg(Color::Red)))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn scope<R, F: FnOnce(&mut ColorWriter<W>) -> R>(&mut self, f: F) -> R {
+        let colorize = self.is_colorized();
+        with_colorize(colorize, || f(self))
+    }
+
+    /// Returns a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Returns a mutable reference to the underlying writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> Write for ColorWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self.filter.as_mut() {
+            Some(filter) => {
+                let mut filtered = Vec::with_capacity(buf.len());
+                filter.filter(buf, &mut filtered);
+                self.writer.write_all(&filtered)?;
+                Ok(buf.len())
+            }
+            None => self.writer.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    fn write_fmt(&mut self, args: fmt::Arguments) -> io::Result<()> {
+        self.scope(|writer| Unscoped(writer).write_fmt(args))
+    }
+}
+
+/// Writes to a `ColorWriter` with the default `write_fmt` implementation.
+struct Unscoped<'a, W: Write>(&'a mut ColorWriter<W>);
+
+impl<W: Write> Write for Unscoped<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::io::Write;
+
+    use crate::{
+        queue, set_color_choice, should_colorize, Color, ColorChoice, ColorWriter, Colorize, SetFg,
+    };
+
+    use super::with_colorize;
+
+    fn auto(vars: &[(&str, &str)], is_tty: bool) -> bool {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+
+        ColorChoice::Auto.should_colorize_with_env(|key| vars.get(key).cloned(), is_tty)
+    }
+
+    #[test]
+    fn test_always_never() {
+        assert!(ColorChoice::Always.should_colorize_with_env(|_| None, false));
+        assert!(!ColorChoice::Never.should_colorize_with_env(|_| None, true));
+    }
+
+    #[test]
+    fn test_auto_follows_color_support() {
+        assert!(auto(&[("TERM", "xterm")], true));
+        assert!(!auto(&[("TERM", "xterm")], false));
+        assert!(!auto(&[("TERM", "dumb")], true));
+    }
+
+    #[test]
+    fn test_auto_without_term() {
+        // The Windows console doesn't set `TERM`.
+        assert_eq!(auto(&[], true), cfg!(windows));
+        assert!(!auto(&[], false));
+    }
+
+    #[test]
+    fn test_no_color() {
+        assert!(!auto(&[("TERM", "xterm"), ("NO_COLOR", "1")], true));
+        assert!(auto(&[("TERM", "xterm"), ("NO_COLOR", "")], true));
+    }
+
+    #[test]
+    fn test_clicolor() {
+        assert!(!auto(&[("TERM", "xterm"), ("CLICOLOR", "0")], true));
+        assert!(auto(&[("CLICOLOR_FORCE", "1")], false));
+        assert!(!auto(&[("CLICOLOR_FORCE", "0")], false));
+    }
+
+    #[test]
+    fn test_force_color() {
+        assert!(auto(&[("FORCE_COLOR", "1"), ("NO_COLOR", "1")], false));
+        assert!(!auto(&[("TERM", "xterm"), ("FORCE_COLOR", "0")], true));
+    }
+
+    #[test]
+    fn test_color_writer_removes_styles() {
+        let mut writer = ColorWriter::new(Vec::new(), ColorChoice::Never, true);
+        write!(writer, "\x1B[38;5;9mred\x1B[0m").unwrap();
+
+        assert!(!writer.is_colorized());
+        assert_eq!(writer.into_inner(), b"red");
+    }
+
+    #[test]
+    fn test_color_writer_keeps_styles() {
+        let mut writer = ColorWriter::new(Vec::new(), ColorChoice::Always, false);
+        write!(writer, "\x1B[38;5;9mred\x1B[0m").unwrap();
+
+        assert!(writer.is_colorized());
+        assert_eq!(writer.into_inner(), b"\x1B[38;5;9mred\x1B[0m");
+    }
+
+    #[test]
+    fn test_color_writer_overrides_global_choice() {
+        set_color_choice(ColorChoice::Always);
+
+        with_colorize(false, || {
+            assert!(!should_colorize());
+
+            let mut writer = ColorWriter::new(Vec::new(), ColorChoice::Always, false);
+            write!(writer, "{}", "red".red()).unwrap();
+            writer
+                .scope(|writer| queue!(writer, SetFg(Color::Blue)))
+                .unwrap();
+
+            assert_eq!(writer.into_inner(), b"\x1B[38;5;9mred\x1B[0m\x1B[38;5;12m");
+            assert!(!should_colorize());
+        });
+
+        let mut writer = ColorWriter::new(Vec::new(), ColorChoice::Never, true);
+        write!(writer, "{}", "red".red()).unwrap();
+
+        assert_eq!(writer.into_inner(), b"red");
+        assert!(should_colorize());
+    }
+}
@@ -0,0 +1,348 @@
+//! This module contains the queries of the terminal colors (OSC 4, 10 & 11).
+
+use std::time::Duration;
+
+use crossterm_utils::Result;
+
+use crate::Color;
+
+const ESC: u8 = 0x1B;
+const BEL: u8 = 0x07;
+
+/// The `Primary Device Attributes` request, answered by all the terminals. It's sent after the
+/// color query, its response tells that the color query won't be answered.
+const DEVICE_ATTRIBUTES_REQUEST: &str = "\x1B[c";
+
+/// A terminal color which can be queried.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ColorQuery {
+    /// The default foreground color (OSC 10).
+    Foreground,
+    /// The default background color (OSC 11).
+    Background,
+    /// A color of the 256 colors palette (OSC 4), e.g. `Palette(1)` for `Color::DarkRed`.
+    Palette(u8),
+}
+
+impl ColorQuery {
+    /// Returns the OSC sequence asking the terminal for the color (`ESC ] 11 ; ? ESC \`).
+    pub fn request(self) -> String {
+        format!("\x1B]{};?\x1B\\", self.params())
+    }
+
+    /// Returns the parameters identifying the color in the request and the response.
+    fn params(self) -> String {
+        match self {
+            ColorQuery::Foreground => "10".to_string(),
+            ColorQuery::Background => "11".to_string(),
+            ColorQuery::Palette(index) => format!("4;{}", index),
+        }
+    }
+}
+
+/// Parses the response of the terminal to a color query.
+///
+/// The response is `OSC params ; rgb:rrrr/gggg/bbbb ST`, with 1 to 4 hex digits per channel.
+/// The `rgba:` and `#rrggbb` forms are supported as well. The response can be surrounded by
+/// other input, e.g. the response to another request. Returns `None` if the response to the
+/// given query isn't found or is malformed.
+///
+/// # Examples
+///
+/// ```
+/// use crossterm_style::{parse_color_response, Color, ColorQuery};
+///
+/// assert_eq!(
+///     parse_color_response(ColorQuery::Background, b"\x1B]11;rgb:2828/2c2c/3434\x1B\\"),
+///     Some(Color::Rgb { r: 40, g: 44, b: 52 })
+/// );
+/// assert_eq!(parse_color_response(ColorQuery::Palette(1), b"\x1B[?64;1c"), None);
+/// ```
+pub fn parse_color_response(query: ColorQuery, response: &[u8]) -> Option<Color> {
+    let prefix = format!("\x1B]{};", query.params());
+    let prefix = prefix.as_bytes();
+
+    let start = response
+        .windows(prefix.len())
+        .position(|window| window == prefix)?
+        + prefix.len();
+    let spec = &response[start..];
+
+    let end = spec
+        .iter()
+        .position(|byte| *byte == BEL || *byte == ESC)
+        .unwrap_or(spec.len());
+
+    parse_color_spec(std::str::from_utf8(&spec[..end]).ok()?)
+}
+
+/// Parses an X11 color specification (`rgb:rr/gg/bb`, `#rrggbb`).
+fn parse_color_spec(spec: &str) -> Option<Color> {
+    let channels: Vec<&str> = if let Some(rgb) = spec.strip_prefix("rgb:") {
+        rgb.split('/').collect()
+    } else if let Some(rgba) = spec.strip_prefix("rgba:") {
+        rgba.split('/').take(3).collect()
+    } else if let Some(hex) = spec.strip_prefix('#') {
+        if hex.is_empty() || hex.len() % 3 != 0 || hex.len() > 12 || !hex.is_ascii() {
+            return None;
+        }
+        let len = hex.len() / 3;
+        vec![&hex[..len], &hex[len..2 * len], &hex[2 * len..]]
+    } else {
+        return None;
+    };
+
+    if channels.len() != 3 {
+        return None;
+    }
+
+    let r = parse_channel(channels[0])?;
+    let g = parse_channel(channels[1])?;
+    let b = parse_channel(channels[2])?;
+
+    Some(Color::Rgb { r, g, b })
+}
+
+/// Parses a channel of 1 to 4 hex digits, scaled to 8 bits.
+fn parse_channel(digits: &str) -> Option<u8> {
+    if digits.is_empty() || digits.len() > 4 {
+        return None;
+    }
+
+    let value = u32::from_str_radix(digits, 16).ok()?;
+    let max = (1u32 << (4 * digits.len())) - 1;
+
+    Some(((value * 255 + max / 2) / max) as u8)
+}
+
+/// Queries the color from the terminal.
+///
+/// The query is written to the terminal (`/dev/tty`) in raw mode and the response is awaited
+/// at most `timeout`. The terminals which don't support the query are detected with a
+/// `Primary Device Attributes` request, so that the timeout is only reached on a slow
+/// connection. Returns `None` if the terminal didn't answer.
+///
+/// # Notes
+///
+/// * The query reads the input of the terminal, it must not run while another thread reads
+///   the input.
+/// * Only UNIX systems are supported, `None` is returned on Windows.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use crossterm_style::{query_color, ColorQuery};
+///
+/// # fn main() -> crossterm_style::Result<()> {
+/// if let Some(background) = query_color(ColorQuery::Background, Duration::from_millis(100))? {
+///     println!("the background color is {:?}", background);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn query_color(query: ColorQuery, timeout: Duration) -> Result<Option<Color>> {
+    let request = format!("{}{}", query.request(), DEVICE_ATTRIBUTES_REQUEST);
+    let response = sys::query_terminal(request.as_bytes(), timeout)?;
+
+    Ok(parse_color_response(query, &response))
+}
+
+/// Returns `true` if the input contains the response to the `Primary Device Attributes`
+/// request (`CSI ? ... c`).
+fn has_device_attributes(input: &[u8]) -> bool {
+    input.windows(3).enumerate().any(|(index, window)| {
+        window == b"\x1B[?"
+            && input[index + 3..]
+                .iter()
+                .find(|byte| !(byte.is_ascii_digit() || **byte == b';'))
+                == Some(&b'c')
+    })
+}
+
+#[cfg(unix)]
+mod sys {
+    use std::fs::{File, OpenOptions};
+    use std::io::{self, Read, Write};
+    use std::mem;
+    use std::os::unix::io::AsRawFd;
+    use std::time::{Duration, Instant};
+
+    use crossterm_utils::Result;
+
+    use super::has_device_attributes;
+
+    /// Restores the terminal mode when dropped.
+    struct ModeGuard<'a> {
+        tty: &'a File,
+        original: libc::termios,
+    }
+
+    impl Drop for ModeGuard<'_> {
+        fn drop(&mut self) {
+            unsafe {
+                libc::tcsetattr(self.tty.as_raw_fd(), libc::TCSANOW, &self.original);
+            }
+        }
+    }
+
+    /// Writes the request to the terminal and returns the input read until the response to
+    /// the `Primary Device Attributes` request or the timeout.
+    pub(super) fn query_terminal(request: &[u8], timeout: Duration) -> Result<Vec<u8>> {
+        let tty = OpenOptions::new().read(true).write(true).open("/dev/tty")?;
+        let fd = tty.as_raw_fd();
+
+        let original = unsafe {
+            let mut termios: libc::termios = mem::zeroed();
+            if libc::tcgetattr(fd, &mut termios) == -1 {
+                return Err(io::Error::last_os_error().into());
+            }
+            termios
+        };
+
+        let mut raw = original;
+        unsafe {
+            libc::cfmakeraw(&mut raw);
+            if libc::tcsetattr(fd, libc::TCSANOW, &raw) == -1 {
+                return Err(io::Error::last_os_error().into());
+            }
+        }
+        let _guard = ModeGuard {
+            tty: &tty,
+            original,
+        };
+
+        (&tty).write_all(request)?;
+        (&tty).flush()?;
+
+        let deadline = Instant::now() + timeout;
+        let mut response = Vec::new();
+        let mut buffer = [0; 256];
+
+        while !has_device_attributes(&response) {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining == Duration::from_secs(0) {
+                break;
+            }
+
+            let mut pollfd = libc::pollfd {
+                fd,
+                events: libc::POLLIN,
+                revents: 0,
+            };
+
+            let ready = unsafe { libc::poll(&mut pollfd, 1, remaining.as_millis() as libc::c_int) };
+            if ready == -1 {
+                let error = io::Error::last_os_error();
+                if error.kind() == io::ErrorKind::Interrupted {
+                    continue;
+                }
+                return Err(error.into());
+            }
+            if ready == 0 {
+                break;
+            }
+
+            let read = (&tty).read(&mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            response.extend_from_slice(&buffer[..read]);
+        }
+
+        Ok(response)
+    }
+}
+
+#[cfg(windows)]
+mod sys {
+    use std::time::Duration;
+
+    use crossterm_utils::Result;
+
+    pub(super) fn query_terminal(_request: &[u8], _timeout: Duration) -> Result<Vec<u8>> {
+        Ok(Vec::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse_color_response, Color, ColorQuery};
+
+    use super::has_device_attributes;
+
+    fn rgb(r: u8, g: u8, b: u8) -> Option<Color> {
+        Some(Color::Rgb { r, g, b })
+    }
+
+    #[test]
+    fn test_request() {
+        assert_eq!(ColorQuery::Foreground.request(), "\x1B]10;?\x1B\\");
+        assert_eq!(ColorQuery::Palette(12).request(), "\x1B]4;12;?\x1B\\");
+    }
+
+    #[test]
+    fn test_parse_responses() {
+        assert_eq!(
+            parse_color_response(ColorQuery::Foreground, b"\x1B]10;rgb:ffff/8080/0000\x07"),
+            rgb(255, 128, 0)
+        );
+        assert_eq!(
+            parse_color_response(ColorQuery::Palette(3), b"\x1B]4;3;rgb:cd/cd/00\x1B\\"),
+            rgb(205, 205, 0)
+        );
+        assert_eq!(
+            parse_color_response(ColorQuery::Background, b"\x1B]11;rgb:f/8/0"),
+            rgb(255, 136, 0)
+        );
+        assert_eq!(
+            parse_color_response(
+                ColorQuery::Background,
+                b"\x1B]11;rgba:0000/0000/ffff/cccc\x07"
+            ),
+            rgb(0, 0, 255)
+        );
+        assert_eq!(
+            parse_color_response(ColorQuery::Background, b"\x1B]11;#102030\x07"),
+            rgb(16, 32, 48)
+        );
+    }
+
+    #[test]
+    fn test_parse_surrounded_response() {
+        let response = b"x\x1B]10;rgb:0/0/0\x07\x1B]11;rgb:1111/2222/3333\x1B\\\x1B[?62;22c";
+
+        assert_eq!(
+            parse_color_response(ColorQuery::Background, response),
+            rgb(17, 34, 51)
+        );
+        assert_eq!(parse_color_response(ColorQuery::Palette(1), response), None);
+        assert_eq!(
+            parse_color_response(ColorQuery::Palette(1), b"\x1B]4;12;rgb:0/0/0\x07"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_malformed_responses() {
+        for response in &[
+            &b"\x1B]11;rgb:00/00\x07"[..],
+            b"\x1B]11;rgb:00000/0/0\x07",
+            b"\x1B]11;rgb:zz/0/0\x07",
+            b"\x1B]11;#12345\x07",
+            b"\x1B]11;red\x07",
+            b"\x1B]11;\x07",
+        ] {
+            assert_eq!(parse_color_response(ColorQuery::Background, response), None);
+        }
+    }
+
+    #[test]
+    fn test_device_attributes() {
+        assert!(has_device_attributes(b"\x1B]11;rgb:0/0/0\x07\x1B[?64;1;2c"));
+        assert!(has_device_attributes(b"\x1B[?6c"));
+        assert!(!has_device_attributes(b"\x1B[?64;1"));
+        assert!(!has_device_attributes(b"\x1B[?25h"));
+    }
+}
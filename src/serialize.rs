@@ -0,0 +1,420 @@
+//! This module contains the serde implementations of the colors, styles and themes.
+//!
+//! The values are written in their string representation (`"#ff0000"`,
+//! `"bold red on black"`), so that they can be written by hand in the configuration files.
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+use serde::de::{self, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{SerializeMap, SerializeSeq, Serializer};
+use serde::{Deserialize, Serialize};
+
+use crate::enums::format_color;
+use crate::{Attribute, Attributes, Color, ObjectStyle, Theme};
+
+/// Deserializes a value from its string representation.
+struct FromStrVisitor<T> {
+    expecting: &'static str,
+    marker: PhantomData<T>,
+}
+
+impl<T> FromStrVisitor<T> {
+    fn new(expecting: &'static str) -> FromStrVisitor<T> {
+        FromStrVisitor {
+            expecting,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'de, T> Visitor<'de> for FromStrVisitor<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.expecting)
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+        value.parse().map_err(E::custom)
+    }
+
+    /// Integers are parsed as well, i.e. the ANSI values of the colors (`9`).
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Self::Value, E> {
+        self.visit_str(&value.to_string())
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Self::Value, E> {
+        self.visit_str(&value.to_string())
+    }
+}
+
+/// Writes the color as `"default"`, `"dark_red"`, `"#ff8800"` or `"ansi(208)"`.
+impl Serialize for Color {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format_color(*self))
+    }
+}
+
+/// Reads the color from a string, see [`Color::from_str`](enum.Color.html#method.from_str).
+///
+/// The human-readable formats accept an ANSI value (`208`) as well.
+impl<'de> Deserialize<'de> for Color {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(FromStrVisitor::new("a color"))
+        } else {
+            deserializer.deserialize_str(FromStrVisitor::new("a color"))
+        }
+    }
+}
+
+/// Writes the attribute as `"bold"`, `"underlined"`, ...
+impl Serialize for Attribute {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+/// Reads the attribute from a string, see
+/// [`Attribute::from_str`](enum.Attribute.html#method.from_str).
+impl<'de> Deserialize<'de> for Attribute {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_str(FromStrVisitor::new("an attribute"))
+    }
+}
+
+/// Writes the attributes as a sequence of attributes (`["bold", "underlined"]`).
+impl Serialize for Attributes {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.len()))?;
+        for attr in self.iter() {
+            seq.serialize_element(&attr)?;
+        }
+        seq.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Attributes {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct AttributesVisitor;
+
+        impl<'de> Visitor<'de> for AttributesVisitor {
+            type Value = Attributes;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a sequence of attributes")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut attrs = Attributes::new();
+                while let Some(attr) = seq.next_element::<Attribute>()? {
+                    attrs.insert(attr);
+                }
+                Ok(attrs)
+            }
+        }
+
+        deserializer.deserialize_seq(AttributesVisitor)
+    }
+}
+
+/// Writes the style in its string representation (`"bold red on black"`).
+impl Serialize for ObjectStyle {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Reads the style from its string representation, see
+/// [String Representation](struct.ObjectStyle.html#string-representation).
+impl<'de> Deserialize<'de> for ObjectStyle {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_str(FromStrVisitor::new("a style"))
+    }
+}
+
+/// Writes the theme as a map of the roles to the styles, the inherited roles included.
+impl Serialize for Theme {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut roles = BTreeMap::new();
+        collect_roles(self, &mut roles);
+
+        let mut map = serializer.serialize_map(Some(roles.len()))?;
+        for (role, style) in roles {
+            map.serialize_entry(role, &style)?;
+        }
+        map.end()
+    }
+}
+
+/// Reads the theme from a map of the roles to the styles.
+///
+/// The human-readable formats accept nested maps as well, their keys are joined into dotted
+/// roles. `{ "diff": { "added": "green" } }` is the `diff.added` role, like the
+/// `diff.added = "green"` TOML dotted key.
+impl<'de> Deserialize<'de> for Theme {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut theme = Theme::new();
+
+        if deserializer.is_human_readable() {
+            for (role, value) in BTreeMap::<String, RoleValue>::deserialize(deserializer)? {
+                value.insert_into(&mut theme, role);
+            }
+        } else {
+            for (role, style) in BTreeMap::<String, ObjectStyle>::deserialize(deserializer)? {
+                theme.insert(&role, style);
+            }
+        }
+
+        Ok(theme)
+    }
+}
+
+/// A value of the theme map, either the style of the role or a map of the sub-roles.
+enum RoleValue {
+    Style(ObjectStyle),
+    Roles(Vec<(String, RoleValue)>),
+}
+
+impl RoleValue {
+    /// Inserts the style of the role, or the styles of the sub-roles, into the theme.
+    fn insert_into(self, theme: &mut Theme, role: String) {
+        match self {
+            RoleValue::Style(style) => {
+                theme.insert(&role, style);
+            }
+            RoleValue::Roles(roles) => {
+                for (sub_role, value) in roles {
+                    value.insert_into(theme, format!("{}.{}", role, sub_role));
+                }
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for RoleValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct RoleValueVisitor;
+
+        impl<'de> Visitor<'de> for RoleValueVisitor {
+            type Value = RoleValue;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a style or a map of roles")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                value.parse().map(RoleValue::Style).map_err(E::custom)
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut roles = Vec::new();
+                while let Some(entry) = map.next_entry::<String, RoleValue>()? {
+                    roles.push(entry);
+                }
+                Ok(RoleValue::Roles(roles))
+            }
+        }
+
+        deserializer.deserialize_any(RoleValueVisitor)
+    }
+}
+
+/// Collects the roles of the theme and of the inherited themes, the roles of the theme
+/// replacing the inherited ones.
+fn collect_roles<'a>(theme: &'a Theme, roles: &mut BTreeMap<&'a str, ObjectStyle>) {
+    if let Some(parent) = theme.parent() {
+        collect_roles(parent, roles);
+    }
+    roles.extend(theme.roles());
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::de::{self, Deserialize, Deserializer, Visitor};
+    use serde::forward_to_deserialize_any;
+
+    use crate::{Attribute, Attributes, Color, Colored, ObjectStyle, Theme};
+
+    /// A deserializer of a non self-describing format, which reads strings only.
+    struct StrDeserializer(&'static str);
+
+    impl<'de> Deserializer<'de> for StrDeserializer {
+        type Error = de::value::Error;
+
+        fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Self::Error> {
+            Err(de::Error::custom("the format is not self-describing"))
+        }
+
+        fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+            visitor.visit_str(self.0)
+        }
+
+        fn is_human_readable(&self) -> bool {
+            false
+        }
+
+        forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char string bytes byte_buf
+            option unit unit_struct newtype_struct seq tuple tuple_struct map struct enum
+            identifier ignored_any
+        }
+    }
+
+    const COLORS: [Color; 19] = [
+        Color::Reset,
+        Color::Black,
+        Color::DarkGrey,
+        Color::Red,
+        Color::DarkRed,
+        Color::Green,
+        Color::DarkGreen,
+        Color::Yellow,
+        Color::DarkYellow,
+        Color::Blue,
+        Color::DarkBlue,
+        Color::Magenta,
+        Color::DarkMagenta,
+        Color::Cyan,
+        Color::DarkCyan,
+        Color::White,
+        Color::Grey,
+        Color::Rgb {
+            r: 255,
+            g: 136,
+            b: 0,
+        },
+        Color::AnsiValue(208),
+    ];
+
+    #[test]
+    fn test_color_round_trip() {
+        for color in COLORS.iter() {
+            let json = serde_json::to_string(color).unwrap();
+            assert_eq!(serde_json::from_str::<Color>(&json).unwrap(), *color);
+        }
+    }
+
+    #[test]
+    fn test_color_representation() {
+        assert_eq!(
+            serde_json::to_string(&Color::Rgb { r: 255, g: 0, b: 0 }).unwrap(),
+            "\"#ff0000\""
+        );
+        assert_eq!(
+            serde_json::to_string(&Color::DarkRed).unwrap(),
+            "\"dark_red\""
+        );
+        assert_eq!(
+            serde_json::from_str::<Color>("\"bright red\"").unwrap(),
+            Color::Red
+        );
+        assert_eq!(
+            serde_json::from_str::<Color>("208").unwrap(),
+            Color::AnsiValue(208)
+        );
+        assert_eq!(
+            serde_json::to_string(&Colored::Fg(Color::Blue)).unwrap(),
+            "{\"Fg\":\"blue\"}"
+        );
+    }
+
+    #[test]
+    fn test_non_self_describing_format() {
+        assert_eq!(
+            Color::deserialize(StrDeserializer("dark_red")),
+            Ok(Color::DarkRed)
+        );
+        assert_eq!(
+            ObjectStyle::deserialize(StrDeserializer("bold red")),
+            Ok("bold red".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_invalid_color() {
+        let error = serde_json::from_str::<Color>("\"redd\"").unwrap_err();
+
+        assert!(error.to_string().contains("did you mean `red`?"));
+        assert!(serde_json::from_str::<Color>("[1, 2]").is_err());
+    }
+
+    #[test]
+    fn test_attributes() {
+        let attrs = Attribute::Bold | Attribute::Underlined;
+        let json = serde_json::to_string(&attrs).unwrap();
+
+        assert_eq!(json, "[\"bold\",\"underlined\"]");
+        assert_eq!(serde_json::from_str::<Attributes>(&json).unwrap(), attrs);
+    }
+
+    #[test]
+    fn test_style_round_trip() {
+        for color in COLORS.iter() {
+            let mut style = ObjectStyle::new().fg(*color).bg(*color);
+            style.add_attr(Attribute::Bold);
+
+            let json = serde_json::to_string(&style).unwrap();
+            assert_eq!(serde_json::from_str::<ObjectStyle>(&json).unwrap(), style);
+        }
+
+        let json = serde_json::to_string(&ObjectStyle::new()).unwrap();
+        assert_eq!(
+            serde_json::from_str::<ObjectStyle>(&json).unwrap(),
+            ObjectStyle::new()
+        );
+    }
+
+    #[test]
+    fn test_theme() {
+        let json = r#"{ "error": "bold red", "diff.added": "green on black" }"#;
+        let theme: Theme = serde_json::from_str(json).unwrap();
+
+        assert_eq!(theme.style("error"), "bold red".parse().unwrap());
+        assert_eq!(theme.style("diff.added.line").bg_color, Some(Color::Black));
+
+        let child = Theme::new()
+            .with("error", "blue".parse().unwrap())
+            .inherit(theme);
+
+        assert_eq!(
+            serde_json::to_string(&child).unwrap(),
+            r#"{"diff.added":"green on black","error":"blue"}"#
+        );
+    }
+
+    #[test]
+    fn test_nested_theme() {
+        let json = r#"{
+            "error": "bold red",
+            "diff": { "added": "green", "removed": { "line": "red on black" } }
+        }"#;
+        let theme: Theme = serde_json::from_str(json).unwrap();
+
+        assert_eq!(theme.get("error"), Some("bold red".parse().unwrap()));
+        assert_eq!(theme.get("diff.added"), Some("green".parse().unwrap()));
+        assert_eq!(
+            theme.get("diff.removed.line"),
+            Some("red on black".parse().unwrap())
+        );
+        assert_eq!(theme.get("diff"), None);
+
+        let error = serde_json::from_str::<Theme>(r#"{ "diff": { "added": 1 } }"#).unwrap_err();
+        assert!(error.to_string().contains("a style or a map of roles"));
+    }
+
+    #[test]
+    fn test_builtin_theme_round_trip() {
+        let theme = Theme::dark();
+        let json = serde_json::to_string(&theme).unwrap();
+
+        assert_eq!(serde_json::from_str::<Theme>(&json).unwrap(), theme);
+    }
+}
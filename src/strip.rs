@@ -0,0 +1,189 @@
+//! This module contains the functions removing the escape sequences from the styled text.
+
+use std::borrow::Cow;
+use std::io::{self, Write};
+
+use crate::filter::EscapeFilter;
+use crate::parser::{Token, Tokens};
+
+/// Removes all the escape sequences from the input.
+///
+/// Both the styles (SGR sequences) and the other escape sequences (cursor movement, window
+/// title, hyperlinks, ...) are removed, malformed sequences are removed up to the unexpected
+/// character. The input is borrowed if it doesn't contain any escape sequence.
+///
+/// # Examples
+///
+/// ```
+/// use crossterm_style::{strip_ansi, Colorize};
+///
+/// let rendered = format!("{} file not found", "Error:".red());
+///
+/// assert_eq!(strip_ansi(&rendered), "Error: file not found");
+/// ```
+pub fn strip_ansi(input: &str) -> Cow<'_, str> {
+    let mut tokens = Tokens::new(input);
+
+    match tokens.next() {
+        None => Cow::Borrowed(input),
+        Some(Token::Text(text)) if text.len() == input.len() => Cow::Borrowed(input),
+        Some(token) => {
+            let mut stripped = String::with_capacity(input.len());
+
+            for token in std::iter::once(token).chain(tokens) {
+                if let Token::Text(text) = token {
+                    stripped.push_str(text);
+                }
+            }
+
+            Cow::Owned(stripped)
+        }
+    }
+}
+
+/// A writer removing all the escape sequences from the written bytes.
+///
+/// The escape sequences are removed the same way as [`strip_ansi`](fn.strip_ansi.html) does.
+/// They can be split across several writes, the incomplete sequences are held back until the
+/// rest of the sequence is written. Invalid UTF-8 is written as is.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::fs::File;
+/// use std::io::Write;
+///
+/// use crossterm_style::{Colorize, StripWriter};
+///
+/// # fn main() -> std::io::Result<()> {
+/// let mut log = StripWriter::new(File::create("log.txt")?);
+///
+/// // Writes a plain `Error`.
+/// write!(log, "{}", "Error".red())?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct StripWriter<W: Write> {
+    writer: W,
+    filter: EscapeFilter,
+}
+
+impl<W: Write> StripWriter<W> {
+    /// Creates a new `StripWriter`.
+    pub fn new(writer: W) -> StripWriter<W> {
+        StripWriter {
+            writer,
+            filter: EscapeFilter::all(),
+        }
+    }
+
+    /// Returns a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Returns a mutable reference to the underlying writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> Write for StripWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut filtered = Vec::with_capacity(buf.len());
+        self.filter.filter(buf, &mut filtered);
+        self.writer.write_all(&filtered)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+    use std::io::Write;
+
+    use crate::{strip_ansi, Colorize, StripWriter, Styler};
+
+    #[test]
+    fn test_strip_ansi() {
+        assert_eq!(
+            strip_ansi("\x1B[1;38;5;9ma\x1B[0m\x1B[2Jb\x1B]0;title\x07c\u{9B}1md"),
+            "abcd"
+        );
+    }
+
+    #[test]
+    fn test_strip_ansi_borrows_plain_text() {
+        assert!(matches!(strip_ansi("plain"), Cow::Borrowed("plain")));
+        assert!(matches!(strip_ansi(""), Cow::Borrowed("")));
+    }
+
+    #[test]
+    fn test_strip_styled_object() {
+        crate::set_color_choice(crate::ColorChoice::Always);
+
+        let styled = crate::style("text").red().on_blue().bold();
+
+        assert_eq!(strip_ansi(&styled.to_string()), "text");
+    }
+
+    #[test]
+    fn test_strip_writer() {
+        let mut writer = StripWriter::new(Vec::new());
+
+        writer.write_all(b"\x1B[31mred\x1B").unwrap();
+        writer
+            .write_all(b"[0m \x1B]8;;https://example.com")
+            .unwrap();
+        writer.write_all(b"\x1B\\link\x1B]8;;\x1B\\").unwrap();
+
+        assert_eq!(writer.into_inner(), b"red link");
+    }
+
+    #[test]
+    fn test_strip_writer_matches_strip_ansi() {
+        let inputs = [
+            "\x1B[1;38;5;9ma\x1B[0mb",
+            "\u{9B}1ma\u{9B}0mb\u{9B}2J",
+            "a\x1B[1\nb\x1B\x01c\x1B(\nd",
+            "a\x1B]0;title\x1B\\b\x1B]8;;url\x07c",
+            "a\x1BPdcs\x1B[1m\x1B\\b",
+            "a\x1B[1;",
+            "a\x1B]0;unterminated",
+            "a\x1B",
+            "\u{e9}\x1B7\x1B#8\u{4e2d}",
+        ];
+
+        for input in inputs.iter() {
+            let mut writer = StripWriter::new(Vec::new());
+            writer.write_all(input.as_bytes()).unwrap();
+            assert_eq!(
+                String::from_utf8(writer.into_inner()).unwrap(),
+                strip_ansi(input),
+                "{:?}",
+                input
+            );
+
+            let mut writer = StripWriter::new(Vec::new());
+            for byte in input.as_bytes() {
+                writer.write_all(&[*byte]).unwrap();
+            }
+            assert_eq!(
+                String::from_utf8(writer.into_inner()).unwrap(),
+                strip_ansi(input),
+                "{:?} written byte by byte",
+                input
+            );
+        }
+    }
+}
@@ -3,21 +3,180 @@
 
 use crossterm_utils::{csi, write_cout, Result};
 
-use crate::{Attribute, Color, Colored, Style};
+use crate::depth::NAMED_COLORS;
+use crate::diff::OFF_GROUPS;
+use crate::{color_depth, Attribute, Color, ColorDepth, Colored, ObjectStyle, Style};
 
 pub(crate) fn set_fg_csi_sequence(fg_color: Color) -> String {
-    format!(csi!("{}m"), Into::<String>::into(Colored::Fg(fg_color)))
+    format!(
+        csi!("{}m"),
+        colored_sgr(Colored::Fg(fg_color), color_depth())
+    )
 }
 
 pub(crate) fn set_bg_csi_sequence(bg_color: Color) -> String {
-    format!(csi!("{}m"), Into::<String>::into(Colored::Bg(bg_color)))
+    format!(
+        csi!("{}m"),
+        colored_sgr(Colored::Bg(bg_color), color_depth())
+    )
+}
+
+/// Returns the SGR parameters of the given color downsampled to the given depth.
+///
+/// The named colors are written with the 256 colors palette syntax (`38;5;n`) unless the depth
+/// is `ColorDepth::Ansi16`, which uses the basic `30 - 37` & `90 - 97` parameters.
+pub(crate) fn colored_sgr(colored: Colored, depth: ColorDepth) -> String {
+    let (color, offset) = match colored {
+        Colored::Fg(color) => (color.downsample(depth), 0),
+        Colored::Bg(color) => (color.downsample(depth), 10),
+    };
+
+    match color.ansi_index() {
+        Some(index) if depth == ColorDepth::Ansi16 && index < 8 => {
+            (30 + offset + index).to_string()
+        }
+        Some(index) if depth == ColorDepth::Ansi16 => (90 + offset + index - 8).to_string(),
+        _ => match colored {
+            Colored::Fg(_) => Colored::Fg(color).into(),
+            Colored::Bg(_) => Colored::Bg(color).into(),
+        },
+    }
 }
 
 pub(crate) fn set_attr_csi_sequence(attribute: Attribute) -> String {
     format!(csi!("{}m"), attribute as i16)
 }
 
-pub(crate) static RESET_CSI_SEQUENCE: &'static str = csi!("0m");
+pub(crate) fn set_style_csi_sequence(style: &ObjectStyle) -> String {
+    let params = style_params(style, color_depth());
+
+    if params.is_empty() {
+        // `CSI m` would reset the style
+        String::new()
+    } else {
+        format!(csi!("{}m"), params.join(";"))
+    }
+}
+
+/// Returns the SGR parameters applying the colors and attributes of the style on top of the
+/// current terminal style.
+///
+/// `Attribute::Reset` comes first, it resets the terminal style before the style is applied.
+pub(crate) fn style_params(style: &ObjectStyle, depth: ColorDepth) -> Vec<String> {
+    let mut params = Vec::new();
+
+    if style.attrs.contains(Attribute::Reset) {
+        params.push((Attribute::Reset as u8).to_string());
+    }
+    if let Some(bg) = style.bg_color {
+        params.push(colored_sgr(Colored::Bg(bg), depth));
+    }
+    if let Some(fg) = style.fg_color {
+        params.push(colored_sgr(Colored::Fg(fg), depth));
+    }
+
+    params.extend(
+        style
+            .attrs
+            .iter()
+            .filter(|attr| *attr != Attribute::Reset)
+            .map(|attr| (attr as u8).to_string()),
+    );
+    params
+}
+
+/// Applies the SGR parameters (`p1;p2;...` of `CSI p1;p2;... m`) to the style.
+///
+/// The style describes the whole terminal state: the default colors are `None` and the
+/// attributes turning something off remove the ones they turn off. The extended colors are
+/// supported in both the `38;5;n` and `38:5:n` forms. Unknown parameters are ignored.
+pub(crate) fn apply_sgr_params(style: &mut ObjectStyle, params: &str) {
+    let mut params = params.split(';');
+
+    while let Some(param) = params.next() {
+        let mut sub_params = param.split(':');
+
+        let code = match sub_params.next() {
+            Some("") | None => 0,
+            Some(code) => match code.parse::<u8>() {
+                Ok(code) => code,
+                Err(_) => continue,
+            },
+        };
+
+        match code {
+            0 => *style = ObjectStyle::new(),
+            21 => style.attrs.remove(Attribute::Bold),
+            30..=37 => style.fg_color = Some(NAMED_COLORS[(code - 30) as usize]),
+            39 => style.fg_color = None,
+            40..=47 => style.bg_color = Some(NAMED_COLORS[(code - 40) as usize]),
+            49 => style.bg_color = None,
+            90..=97 => style.fg_color = Some(NAMED_COLORS[(code - 90 + 8) as usize]),
+            100..=107 => style.bg_color = Some(NAMED_COLORS[(code - 100 + 8) as usize]),
+            // 58 is the underline color, its arguments are skipped
+            38 | 48 | 58 => {
+                let color = if param.contains(':') {
+                    extended_color(&mut sub_params, true)
+                } else {
+                    extended_color(&mut params, false)
+                };
+
+                match (code, color) {
+                    (38, Some(color)) => style.fg_color = Some(color),
+                    (48, Some(color)) => style.bg_color = Some(color),
+                    _ => {}
+                }
+            }
+            _ => {
+                if let Some(attr) = Attribute::from_sgr(code) {
+                    match OFF_GROUPS.iter().find(|(off, _)| *off == attr) {
+                        Some((_, group)) => group.iter().for_each(|attr| style.attrs.remove(*attr)),
+                        None => style.attrs.insert(attr),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Reads the arguments of an extended color (`5;n` or `2;r;g;b`).
+///
+/// The colon separated form may contain a color space identifier (`2:id:r:g:b`).
+fn extended_color<'a, I>(args: &mut I, colon_separated: bool) -> Option<Color>
+where
+    I: Iterator<Item = &'a str>,
+{
+    match args.next()? {
+        "5" => {
+            let value = args.next()?.parse::<u8>().ok()?;
+
+            Some(if value < 16 {
+                NAMED_COLORS[value as usize]
+            } else {
+                Color::AnsiValue(value)
+            })
+        }
+        "2" => {
+            let mut values: Vec<&str> = args.take(if colon_separated { 4 } else { 3 }).collect();
+
+            if values.len() == 4 {
+                values.remove(0);
+            }
+
+            match values.as_slice() {
+                [r, g, b] => Some(Color::Rgb {
+                    r: r.parse().ok()?,
+                    g: g.parse().ok()?,
+                    b: b.parse().ok()?,
+                }),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+pub(crate) static RESET_CSI_SEQUENCE: &str = csi!("0m");
 
 /// This struct is an ANSI escape code implementation for color related actions.
 pub(crate) struct AnsiColor;
@@ -107,7 +266,12 @@ impl From<Colored> for String {
 
 #[cfg(test)]
 mod tests {
-    use crate::{Color, Colored};
+    use crate::{
+        set_color_choice, Attribute, Color, ColorChoice, ColorDepth, Colored, Command, ObjectStyle,
+        SetAttributes, SetColors, SetStyle,
+    };
+
+    use super::{apply_sgr_params, colored_sgr, style_params};
 
     #[test]
     fn test_parse_fg_color() {
@@ -144,4 +308,178 @@ mod tests {
         let colored = Colored::Fg(Color::AnsiValue(255));
         assert_eq!(Into::<String>::into(colored), "38;5;255");
     }
+
+    #[test]
+    fn test_colored_sgr_16_colors() {
+        assert_eq!(
+            colored_sgr(Colored::Fg(Color::DarkRed), ColorDepth::Ansi16),
+            "31"
+        );
+        assert_eq!(
+            colored_sgr(Colored::Fg(Color::Red), ColorDepth::Ansi16),
+            "91"
+        );
+        assert_eq!(
+            colored_sgr(Colored::Bg(Color::Grey), ColorDepth::Ansi16),
+            "47"
+        );
+        assert_eq!(
+            colored_sgr(Colored::Bg(Color::White), ColorDepth::Ansi16),
+            "107"
+        );
+        assert_eq!(
+            colored_sgr(Colored::Fg(Color::Reset), ColorDepth::Ansi16),
+            "39"
+        );
+    }
+
+    #[test]
+    fn test_colored_sgr_downsamples_color() {
+        let rgb = Color::Rgb { r: 255, g: 0, b: 0 };
+
+        assert_eq!(
+            colored_sgr(Colored::Fg(rgb), ColorDepth::TrueColor),
+            "38;2;255;0;0"
+        );
+        assert_eq!(
+            colored_sgr(Colored::Fg(rgb), ColorDepth::Ansi256),
+            "38;5;196"
+        );
+        assert_eq!(colored_sgr(Colored::Bg(rgb), ColorDepth::Ansi16), "101");
+    }
+
+    #[test]
+    fn test_style_params() {
+        let mut style = ObjectStyle::new().fg(Color::Red).bg(Color::Blue);
+        style.add_attr(Attribute::Bold);
+        style.add_attr(Attribute::NoItalic);
+
+        assert_eq!(
+            style_params(&style, ColorDepth::Ansi16),
+            vec!["104", "91", "1", "23"]
+        );
+        assert!(style_params(&ObjectStyle::new(), ColorDepth::Ansi16).is_empty());
+    }
+
+    fn parse_sgr(params: &str) -> ObjectStyle {
+        let mut style = ObjectStyle::new();
+        apply_sgr_params(&mut style, params);
+        style
+    }
+
+    #[test]
+    fn test_apply_named_colors() {
+        assert_eq!(
+            parse_sgr("31;104"),
+            ObjectStyle::new().fg(Color::DarkRed).bg(Color::Blue)
+        );
+        assert_eq!(parse_sgr("38;5;9"), ObjectStyle::new().fg(Color::Red));
+        assert_eq!(parse_sgr("31;39"), ObjectStyle::new());
+    }
+
+    #[test]
+    fn test_apply_extended_colors() {
+        assert_eq!(
+            parse_sgr("38;2;1;2;3;48;5;100"),
+            ObjectStyle::new()
+                .fg(Color::Rgb { r: 1, g: 2, b: 3 })
+                .bg(Color::AnsiValue(100))
+        );
+        assert_eq!(
+            parse_sgr("38:2::1:2:3;48:5:100"),
+            ObjectStyle::new()
+                .fg(Color::Rgb { r: 1, g: 2, b: 3 })
+                .bg(Color::AnsiValue(100))
+        );
+        assert_eq!(
+            parse_sgr("38:2:1:2:3"),
+            ObjectStyle::new().fg(Color::Rgb { r: 1, g: 2, b: 3 })
+        );
+        assert_eq!(parse_sgr("58;5;1;4"), parse_sgr("4"));
+    }
+
+    #[test]
+    fn test_apply_attributes() {
+        let style = parse_sgr("1;2;3;4;22;23");
+        assert_eq!(style.attrs, Attribute::Underlined.into());
+
+        assert_eq!(parse_sgr("1;31;0"), ObjectStyle::new());
+        assert_eq!(parse_sgr("1;31;"), ObjectStyle::new());
+    }
+
+    #[test]
+    fn test_apply_malformed_params() {
+        assert_eq!(parse_sgr("foo;1;999;38;5"), parse_sgr("1"));
+        assert_eq!(parse_sgr("38;2;1;2"), ObjectStyle::new());
+    }
+
+    #[test]
+    fn test_set_style_empty() {
+        set_color_choice(ColorChoice::Always);
+
+        assert_eq!(SetStyle(ObjectStyle::new()).ansi_code(), "");
+        assert_eq!(SetColors { fg: None, bg: None }.ansi_code(), "");
+        assert_eq!(SetAttributes(Default::default()).ansi_code(), "");
+    }
+
+    #[test]
+    fn test_set_colors() {
+        set_color_choice(ColorChoice::Always);
+
+        assert_eq!(
+            SetColors {
+                fg: Some(Color::Red),
+                bg: Some(Color::Rgb { r: 1, g: 2, b: 3 }),
+            }
+            .ansi_code(),
+            "\x1B[48;2;1;2;3;38;5;9m"
+        );
+        assert_eq!(
+            SetColors {
+                fg: None,
+                bg: Some(Color::Reset),
+            }
+            .ansi_code(),
+            "\x1B[49m"
+        );
+    }
+
+    #[test]
+    fn test_set_attributes() {
+        set_color_choice(ColorChoice::Always);
+
+        assert_eq!(
+            SetAttributes(Attribute::Bold | Attribute::Underlined).ansi_code(),
+            "\x1B[1;4m"
+        );
+        assert_eq!(
+            SetAttributes(Attribute::Reset.into()).ansi_code(),
+            "\x1B[0m"
+        );
+    }
+
+    #[test]
+    fn test_set_style() {
+        set_color_choice(ColorChoice::Always);
+
+        let mut style = ObjectStyle::new().fg(Color::Blue).bg(Color::AnsiValue(100));
+        style.add_attr(Attribute::Italic);
+        style.add_attr(Attribute::CrossedOut);
+
+        assert_eq!(SetStyle(style).ansi_code(), "\x1B[48;5;100;38;5;12;3;9m");
+    }
+
+    #[test]
+    fn test_set_style_reset_first() {
+        set_color_choice(ColorChoice::Always);
+
+        let style = ObjectStyle::from_git("reset red bold").unwrap();
+
+        assert_eq!(SetStyle(style).ansi_code(), "\x1B[0;38;5;1;1m");
+        assert_eq!(parse_sgr("0;38;5;1;1"), {
+            let mut expected = ObjectStyle::new().fg(Color::DarkRed);
+            expected.add_attr(Attribute::Bold);
+            expected
+        });
+    }
 }
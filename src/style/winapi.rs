@@ -9,7 +9,7 @@ use winapi::um::wincon;
 use crossterm_winapi::{Console, Handle, HandleType, ScreenBuffer};
 use lazy_static::lazy_static;
 
-use crate::{Color, Colored, Style};
+use crate::{Color, ColorDepth, Colored, Style};
 
 const FG_GREEN: u16 = wincon::FOREGROUND_GREEN;
 const FG_RED: u16 = wincon::FOREGROUND_RED;
@@ -122,9 +122,10 @@ impl From<Colored> for u16 {
                         (original_color & !(REMOVE_BG_MASK))
                     }
 
-                    /* WinApi will be used for systems that do not support ANSI, those are windows version less then 10. RGB and 255 (AnsiBValue) colors are not supported in that case.*/
-                    Color::Rgb { r: _, g: _, b: _ } => 0,
-                    Color::AnsiValue(_val) => 0,
+                    /* WinApi will be used for systems that do not support ANSI, those are windows version less then 10. RGB and 255 (AnsiBValue) colors are not supported in that case, they're downsampled to the nearest named color.*/
+                    Color::Rgb { .. } | Color::AnsiValue(_) => {
+                        Colored::Fg(color.downsample(ColorDepth::Ansi16)).into()
+                    }
                 }
             }
             Colored::Bg(color) => {
@@ -153,9 +154,10 @@ impl From<Colored> for u16 {
                         // remove all foreground values from the original color, we don't want to reset those.
                         (original_color & !(REMOVE_FG_MASK))
                     }
-                    /* WinApi will be used for systems that do not support ANSI, those are windows version less then 10. RGB and 255 (AnsiBValue) colors are not supported in that case.*/
-                    Color::Rgb { r: _, g: _, b: _ } => 0,
-                    Color::AnsiValue(_val) => 0,
+                    /* WinApi will be used for systems that do not support ANSI, those are windows version less then 10. RGB and 255 (AnsiBValue) colors are not supported in that case, they're downsampled to the nearest named color.*/
+                    Color::Rgb { .. } | Color::AnsiValue(_) => {
+                        Colored::Bg(color.downsample(ColorDepth::Ansi16)).into()
+                    }
                 }
             }
         }
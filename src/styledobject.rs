@@ -1,14 +1,37 @@
 //! This module contains the logic to style an object that contains some 'content' which can be styled.
 
-use std::fmt::{self, Display, Formatter};
+use std::cell::RefCell;
+use std::fmt::{self, Alignment, Display, Formatter, Write};
 use std::result;
 
-use crossterm_utils::queue;
+use crossterm_utils::{queue, Command};
 
-use crate::{Attribute, Color, Colorize, ObjectStyle, ResetColor, SetAttr, SetBg, SetFg, Styler};
+use crate::{
+    ansi_width, sgr_diff, should_colorize, Attribute, Color, Colorize, ObjectStyle, ResetColor,
+    SetStyle, Span, StyledText, Styler,
+};
 
 /// A styled object.
 ///
+/// The style is not written if the global [`ColorChoice`](enum.ColorChoice.html) disables the
+/// styles, only the content is.
+///
+/// # Nesting
+///
+/// A styled object written while writing the content of another one (the content is a
+/// `StyledObject` or writes one in its `Display` implementation) restores the style of the
+/// enclosing object when it finishes, instead of resetting all the colors and attributes.
+/// Content already rendered to a `String` (`format!`) can't be restored.
+///
+/// # Formatting
+///
+/// The width (`{:10}`), fill and alignment (`{:*^10}`) pad the content by its visible width,
+/// the padding has the style of the object unless [`Padding::Unstyled`](enum.Padding.html) is
+/// set. Without an alignment, the content is aligned like its own `Display` aligns it, e.g.
+/// the numbers are right-aligned and the strings are left-aligned. The sign (`{:+}`),
+/// alternate (`{:#}`), zero (`{:06}`) and precision (`{:.2}`) flags are forwarded to the
+/// content.
+///
 /// # Examples
 ///
 /// ```rust
@@ -27,9 +50,37 @@ pub struct StyledObject<D: Display + Clone> {
     pub object_style: ObjectStyle,
     /// An object to apply the style on.
     pub content: D,
+    /// The style of the padding added by the formatting width.
+    pub(crate) padding: Padding,
 }
 
-impl<'a, D: Display + 'a + Clone> StyledObject<D> {
+/// The style of the padding added by the formatting width (`{:>10}`) of a `StyledObject`.
+///
+/// The content is padded by its visible width, see [`ansi_width`](fn.ansi_width.html).
+///
+/// # Examples
+///
+/// ```
+/// use crossterm_style::{set_color_choice, ColorChoice, Colorize, Padding};
+///
+/// set_color_choice(ColorChoice::Always);
+///
+/// assert_eq!(format!("{:>4}", "ok".red()), "\x1B[38;5;9m  ok\x1B[0m");
+/// assert_eq!(
+///     format!("{:>4}", "ok".red().padding(Padding::Unstyled)),
+///     "  \x1B[38;5;9mok\x1B[0m"
+/// );
+/// ```
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub enum Padding {
+    /// The padding has the style of the object.
+    #[default]
+    Styled,
+    /// The padding is written outside of the style of the object.
+    Unstyled,
+}
+
+impl<D: Display + Clone> StyledObject<D> {
     /// Sets the foreground color.
     pub fn with(mut self, foreground_color: Color) -> StyledObject<D> {
         self.object_style = self.object_style.fg(foreground_color);
@@ -49,33 +100,253 @@ impl<'a, D: Display + 'a + Clone> StyledObject<D> {
         self.object_style.add_attr(attr);
         self
     }
+
+    /// Sets the style of the padding added by the formatting width.
+    pub fn padding(mut self, padding: Padding) -> StyledObject<D> {
+        self.padding = padding;
+        self
+    }
+
+    /// Returns the content cut to `width` columns, ending with `ellipsis` if it was cut.
+    ///
+    /// See [`StyledText::truncate`](struct.StyledText.html#method.truncate) for more info.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossterm_style::Colorize;
+    ///
+    /// let path = "/home/user/documents/report.txt".blue();
+    ///
+    /// assert_eq!(path.truncate(14, "...").plain(), "/home/user/...");
+    /// ```
+    pub fn truncate(&self, width: usize, ellipsis: &str) -> StyledText {
+        StyledText::from(self.clone()).truncate(width, ellipsis)
+    }
+
+    /// Returns the content cut to `width` columns, ending with the styled `ellipsis` if it
+    /// was cut.
+    ///
+    /// See [`StyledText::truncate_with`](struct.StyledText.html#method.truncate_with) for more
+    /// info.
+    pub fn truncate_with(&self, width: usize, ellipsis: Span) -> StyledText {
+        StyledText::from(self.clone()).truncate_with(width, ellipsis)
+    }
+
+    /// Wraps the content to lines of at most `width` columns.
+    ///
+    /// See [`StyledText::wrap`](struct.StyledText.html#method.wrap) for more info.
+    pub fn wrap(&self, width: usize) -> Vec<StyledText> {
+        StyledText::from(self.clone()).wrap(width)
+    }
+
+    /// Returns the number of columns taken by the content on the terminal.
+    ///
+    /// The escape sequences written by the content are ignored, see
+    /// [`ansi_width`](fn.ansi_width.html) for more info.
+    pub fn width(&self) -> usize {
+        ansi_width(&self.content.to_string())
+    }
 }
 
-impl<D: Display + Clone> Display for StyledObject<D> {
-    fn fmt(&self, f: &mut Formatter) -> result::Result<(), fmt::Error> {
-        let mut reset = false;
+impl<D: Display + Clone> StyledObject<D> {
+    /// Writes the content with the style, the content is written by `write_content`.
+    fn write_styled<F>(&self, f: &mut Formatter, write_content: F) -> fmt::Result
+    where
+        F: FnOnce(&mut Formatter) -> fmt::Result,
+    {
+        if !should_colorize() {
+            return write_content(f);
+        }
+
+        let outer_style = enclosing_style();
 
-        if let Some(bg) = self.object_style.bg_color {
-            queue!(f, SetBg(bg)).map_err(|_| fmt::Error)?;
-            reset = true;
+        let styled = !self.object_style.is_empty();
+        queue!(f, SetStyle(self.object_style)).map_err(|_| fmt::Error)?;
+
+        let style = self.nested_style();
+
+        {
+            let _guard = StyleStackGuard::push(style);
+            write_content(f)?;
         }
-        if let Some(fg) = self.object_style.fg_color {
-            queue!(f, SetFg(fg)).map_err(|_| fmt::Error)?;
-            reset = true;
+
+        if styled {
+            match outer_style {
+                Some(outer_style) => queue!(
+                    f,
+                    ChangeStyle {
+                        from: style,
+                        to: outer_style
+                    }
+                ),
+                None => queue!(f, ResetColor),
+            }
+            .map_err(|_| fmt::Error)?;
         }
 
-        for attr in self.object_style.attrs.iter() {
-            queue!(f, SetAttr(*attr)).map_err(|_| fmt::Error)?;
-            reset = true;
+        Ok(())
+    }
+
+    /// Returns the style of the object applied on top of the style of the enclosing object,
+    /// the one restored by the nested objects.
+    fn nested_style(&self) -> ObjectStyle {
+        match enclosing_style() {
+            Some(outer_style) => outer_style.merge(self.object_style),
+            None => self.object_style,
         }
+    }
 
-        fmt::Display::fmt(&self.content, f)?;
+    /// Writes the content to a `String` with the sign (`+`), alternate (`#`) and zero (`0`)
+    /// flags and the precision of the formatter, and the given width without alignment.
+    ///
+    /// The nested objects restore the style of this object, as if the content was written
+    /// directly.
+    fn render_content(&self, f: &Formatter, zero: bool, width: usize) -> String {
+        let _guard = if should_colorize() {
+            Some(StyleStackGuard::push(self.nested_style()))
+        } else {
+            None
+        };
 
-        if reset {
-            queue!(f, ResetColor).map_err(|_| fmt::Error)?;
+        macro_rules! render {
+            ($($flag:literal),*) => {
+                match f.precision() {
+                    Some(precision) => format!(
+                        concat!("{:", $($flag,)* "width$.precision$}"),
+                        self.content,
+                        width = width,
+                        precision = precision
+                    ),
+                    None => format!(
+                        concat!("{:", $($flag,)* "width$}"),
+                        self.content,
+                        width = width
+                    ),
+                }
+            };
         }
 
-        Ok(())
+        match (f.sign_plus(), f.alternate(), zero) {
+            (false, false, false) => render!(),
+            (false, false, true) => render!("0"),
+            (false, true, false) => render!("#"),
+            (false, true, true) => render!("#", "0"),
+            (true, false, false) => render!("+"),
+            (true, false, true) => render!("+", "0"),
+            (true, true, false) => render!("+", "#"),
+            (true, true, true) => render!("+", "#", "0"),
+        }
+    }
+
+    /// Returns the alignment of the content when the formatter has no alignment.
+    ///
+    /// The content is written again one character wider, the side getting the fill is the
+    /// default alignment of the content (right for the numbers, left for the strings).
+    fn default_alignment(&self, f: &Formatter, content: &str) -> Alignment {
+        let padded = self.render_content(f, false, content.chars().count() + 1);
+
+        if padded != content && padded.ends_with(content) {
+            Alignment::Right
+        } else {
+            Alignment::Left
+        }
+    }
+}
+
+impl<D: Display + Clone> Display for StyledObject<D> {
+    fn fmt(&self, f: &mut Formatter) -> result::Result<(), fmt::Error> {
+        let width = match f.width() {
+            Some(width) => width,
+            None => return self.write_styled(f, |f| fmt::Display::fmt(&self.content, f)),
+        };
+
+        // The content is padded by its visible width, the escape sequences and the wide
+        // characters would throw off the padding done by the content itself. The zero padding
+        // is left to the content, it goes after the sign.
+        let zero = f.sign_aware_zero_pad();
+        let content = self.render_content(f, zero, if zero { width } else { 0 });
+
+        let padding = width.saturating_sub(ansi_width(&content));
+        let alignment = match f.align() {
+            Some(alignment) => alignment,
+            None if padding == 0 => Alignment::Left,
+            None => self.default_alignment(f, &content),
+        };
+
+        let (left, right) = match alignment {
+            Alignment::Left => (0, padding),
+            Alignment::Right => (padding, 0),
+            Alignment::Center => (padding / 2, padding - padding / 2),
+        };
+
+        let fill = f.fill();
+        let write_fill = |f: &mut Formatter, count: usize| -> fmt::Result {
+            for _ in 0..count {
+                f.write_char(fill)?;
+            }
+            Ok(())
+        };
+
+        match self.padding {
+            Padding::Styled => self.write_styled(f, |f| {
+                write_fill(f, left)?;
+                f.write_str(&content)?;
+                write_fill(f, right)
+            }),
+            Padding::Unstyled => {
+                write_fill(f, left)?;
+                self.write_styled(f, |f| f.write_str(&content))?;
+                write_fill(f, right)
+            }
+        }
+    }
+}
+
+thread_local! {
+    /// The styles of the `StyledObject`s being written, the innermost is the last one.
+    static STYLE_STACK: RefCell<Vec<ObjectStyle>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Returns the style of the `StyledObject` being written, if any.
+pub(crate) fn enclosing_style() -> Option<ObjectStyle> {
+    STYLE_STACK.with(|stack| stack.borrow().last().copied())
+}
+
+/// Pushes a style on the `STYLE_STACK` and pops it when dropped.
+struct StyleStackGuard;
+
+impl StyleStackGuard {
+    fn push(style: ObjectStyle) -> StyleStackGuard {
+        STYLE_STACK.with(|stack| stack.borrow_mut().push(style));
+        StyleStackGuard
+    }
+}
+
+impl Drop for StyleStackGuard {
+    fn drop(&mut self) {
+        STYLE_STACK.with(|stack| stack.borrow_mut().pop());
+    }
+}
+
+/// A command to change the terminal style from one style to another, e.g. back to the one of
+/// the enclosing object.
+pub(crate) struct ChangeStyle {
+    pub(crate) from: ObjectStyle,
+    pub(crate) to: ObjectStyle,
+}
+
+impl Command for ChangeStyle {
+    type AnsiType = String;
+
+    fn ansi_code(&self) -> Self::AnsiType {
+        sgr_diff(&self.from, &self.to)
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> crossterm_utils::Result<()> {
+        ResetColor.execute_winapi()?;
+        SetStyle(self.to).execute_winapi()
     }
 }
 
@@ -133,7 +404,12 @@ impl<D: Display + Clone> Styler<D> for StyledObject<D> {
 
 #[cfg(test)]
 mod tests {
-    use crate::{Attribute, Color, ObjectStyle};
+    use std::cell::Cell;
+    use std::fmt::{self, Display, Formatter};
+
+    use crate::{
+        set_color_choice, style, Attribute, Color, ColorChoice, Colorize, ObjectStyle, Padding,
+    };
 
     #[test]
     fn test_set_fg_bg_add_attr() {
@@ -150,7 +426,149 @@ mod tests {
         assert_eq!(styled_object.object_style.fg_color, Some(Color::Green));
         assert_eq!(styled_object.object_style.bg_color, Some(Color::Magenta));
         assert_eq!(styled_object.object_style.attrs.len(), 2);
-        assert_eq!(styled_object.object_style.attrs[0], Attribute::Reset);
-        assert_eq!(styled_object.object_style.attrs[1], Attribute::NoItalic);
+        assert!(styled_object.object_style.attrs.contains(Attribute::Reset));
+        assert!(styled_object
+            .object_style
+            .attrs
+            .contains(Attribute::NoItalic));
+    }
+
+    #[derive(Clone)]
+    struct Sentence;
+
+    impl Display for Sentence {
+        fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+            write!(f, "a {} c", "b".blue())
+        }
+    }
+
+    #[test]
+    fn test_nested_styled_object_restores_outer_style() {
+        set_color_choice(ColorChoice::Always);
+
+        assert_eq!(
+            style(Sentence).with(Color::Red).to_string(),
+            "\x1B[38;5;9ma \x1B[38;5;12mb\x1B[38;5;9m c\x1B[0m"
+        );
+        assert_eq!(Sentence.to_string(), "a \x1B[38;5;12mb\x1B[0m c");
+    }
+
+    #[test]
+    fn test_nested_styled_object_restores_merged_style() {
+        set_color_choice(ColorChoice::Always);
+
+        let inner = style("b").on(Color::Green).attr(Attribute::Italic);
+        let middle = style(inner).with(Color::Blue);
+        let outer = style(middle).attr(Attribute::Bold);
+
+        assert_eq!(
+            outer.to_string(),
+            "\x1B[1m\x1B[38;5;12m\x1B[48;5;10;3mb\x1B[23;49m\x1B[39m\x1B[0m"
+        );
+    }
+
+    #[test]
+    fn test_pad_by_visible_width() {
+        set_color_choice(ColorChoice::Always);
+
+        assert_eq!(format!("{:6}|", "日本".red()), "\x1B[38;5;9m日本  \x1B[0m|");
+        assert_eq!(format!("{:*^7}", "ab".red()), "\x1B[38;5;9m**ab***\x1B[0m");
+        assert_eq!(format!("{:2}", "abc".red()), "\x1B[38;5;9mabc\x1B[0m");
+    }
+
+    #[test]
+    fn test_pad_default_alignment() {
+        set_color_choice(ColorChoice::Always);
+
+        assert_eq!(
+            format!("{:4}", style(42).with(Color::Red)),
+            "\x1B[38;5;9m  42\x1B[0m"
+        );
+        assert_eq!(
+            format!("{:<4}", style(42).with(Color::Red)),
+            "\x1B[38;5;9m42  \x1B[0m"
+        );
+        assert_eq!(format!("{:4}|", style("42")), "42  |");
+        assert_eq!(format!("{:4}|", style(String::from("42"))), "42  |");
+        assert_eq!(format!("{:4}|", style(42)), "  42|");
+        assert_eq!(format!("{:6}|", style(-1.5)), "  -1.5|");
+        assert_eq!(
+            format!("{:6}", style(Sentence).with(Color::Red)),
+            "\x1B[38;5;9ma \x1B[38;5;12mb\x1B[38;5;9m c \x1B[0m"
+        );
+    }
+
+    #[derive(Clone)]
+    struct Counted<'a>(&'a Cell<usize>);
+
+    impl Display for Counted<'_> {
+        fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+            self.0.set(self.0.get() + 1);
+            f.pad("ab")
+        }
+    }
+
+    #[test]
+    fn test_pad_renders_content_once() {
+        set_color_choice(ColorChoice::Always);
+
+        let count = Cell::new(0);
+        let styled = style(Counted(&count)).with(Color::Red);
+
+        assert_eq!(format!("{:<4}", styled), "\x1B[38;5;9mab  \x1B[0m");
+        assert_eq!(count.get(), 1);
+
+        assert_eq!(
+            format!("{:>4}", styled.padding(Padding::Unstyled)),
+            "  \x1B[38;5;9mab\x1B[0m"
+        );
+        assert_eq!(count.get(), 2);
+    }
+
+    #[test]
+    fn test_pad_unstyled() {
+        set_color_choice(ColorChoice::Always);
+
+        assert_eq!(
+            format!("{:^6}", "ab".red().padding(Padding::Unstyled)),
+            "  \x1B[38;5;9mab\x1B[0m  "
+        );
+    }
+
+    #[test]
+    fn test_precision() {
+        set_color_choice(ColorChoice::Always);
+
+        assert_eq!(format!("{:.2}", "abc".red()), "\x1B[38;5;9mab\x1B[0m");
+        assert_eq!(
+            format!("{:>6.2}", style(1.0 / 3.0).with(Color::Red)),
+            "\x1B[38;5;9m  0.33\x1B[0m"
+        );
+    }
+
+    #[test]
+    fn test_pad_with_flags() {
+        set_color_choice(ColorChoice::Always);
+
+        assert_eq!(
+            format!("{:+6}", style(42).with(Color::Red)),
+            "\x1B[38;5;9m   +42\x1B[0m"
+        );
+        assert_eq!(
+            format!("{:06}", style(-42).with(Color::Red)),
+            "\x1B[38;5;9m-00042\x1B[0m"
+        );
+        assert_eq!(
+            format!("{:+08.2}", style(1.5).with(Color::Red)),
+            "\x1B[38;5;9m+0001.50\x1B[0m"
+        );
+    }
+
+    #[test]
+    fn test_width() {
+        set_color_choice(ColorChoice::Always);
+
+        assert_eq!(style("日本").with(Color::Red).width(), 4);
+        assert_eq!(style(Sentence).with(Color::Red).width(), 5);
     }
 }
@@ -0,0 +1,373 @@
+//! This module contains the logic to detect how many colors the terminal supports.
+
+use std::env;
+
+use crate::ColorDepth;
+
+/// Represents the color support level of the terminal.
+///
+/// # Examples
+///
+/// ```no_run
+/// use crossterm_style::{set_color_depth, ColorSupport};
+///
+/// match ColorSupport::detect().depth() {
+///     Some(depth) => set_color_depth(depth),
+///     None => println!("Colors are not supported."),
+/// }
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Ord, PartialOrd, Hash)]
+pub enum ColorSupport {
+    /// Colors are not supported (`TERM=dumb`, output redirected to a file, ...).
+    None,
+    /// The 16 named colors are supported.
+    Ansi16,
+    /// The 256 colors palette is supported.
+    Ansi256,
+    /// 24-bit colors are supported.
+    TrueColor,
+}
+
+impl ColorSupport {
+    /// Detects the color support of the standard output.
+    ///
+    /// See [`from_env`](#method.from_env) for the list of checks.
+    pub fn detect() -> ColorSupport {
+        ColorSupport::detect_with_env(|key| env::var(key).ok(), is_stdout_tty())
+    }
+
+    /// Detects the color support from the given environment, falling back to the support of
+    /// the Windows console.
+    ///
+    /// Same as [`from_env`](#method.from_env) except that a terminal without the `TERM`
+    /// variable is assumed to be the Windows console, which doesn't set it.
+    pub(crate) fn detect_with_env<F>(var: F, is_tty: bool) -> ColorSupport
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        with_console_fallback(var, is_tty, console_color_support)
+    }
+
+    /// Detects the color support from the given environment.
+    ///
+    /// The `var` function returns the value of the environment variable with the given name and
+    /// `is_tty` tells if the stream is a terminal. The following checks are made:
+    ///
+    /// * `TERM=dumb` has no colors.
+    /// * Streams which are not a terminal have no colors, unless running on a CI service known
+    ///   to render them (`GITHUB_ACTIONS`, `GITLAB_CI`, `TRAVIS`, ...).
+    /// * `COLORTERM=truecolor` or `COLORTERM=24bit` and the `*-direct` `TERM` families
+    ///   have 24-bit colors.
+    /// * Some terminals are recognized by the `TERM_PROGRAM` variable (`iTerm.app`,
+    ///   `Apple_Terminal`, `vscode`, ...).
+    /// * The `*-256color` `TERM` families have the 256 colors palette.
+    /// * The `linux`, `xterm`, `screen`, `rxvt`, ... `TERM` families have the 16 named colors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossterm_style::ColorSupport;
+    ///
+    /// let support = ColorSupport::from_env(
+    ///     |key| match key {
+    ///         "TERM" => Some("xterm-256color".to_string()),
+    ///         _ => None,
+    ///     },
+    ///     true,
+    /// );
+    ///
+    /// assert_eq!(support, ColorSupport::Ansi256);
+    /// ```
+    pub fn from_env<F>(var: F, is_tty: bool) -> ColorSupport
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let term = var("TERM").unwrap_or_default();
+
+        if term == "dumb" {
+            return ColorSupport::None;
+        }
+
+        if !is_tty {
+            return ci_color_support(&var).unwrap_or(ColorSupport::None);
+        }
+
+        if let Some(colorterm) = var("COLORTERM") {
+            if colorterm == "truecolor" || colorterm == "24bit" {
+                return ColorSupport::TrueColor;
+            }
+        }
+
+        if term.ends_with("-direct") || term.contains("truecolor") || term.contains("24bit") {
+            return ColorSupport::TrueColor;
+        }
+
+        if let Some(program) = var("TERM_PROGRAM") {
+            match program.as_str() {
+                "iTerm.app" => {
+                    let major_version = var("TERM_PROGRAM_VERSION")
+                        .and_then(|version| version.split('.').next()?.parse::<u32>().ok())
+                        .unwrap_or(0);
+
+                    return if major_version >= 3 {
+                        ColorSupport::TrueColor
+                    } else {
+                        ColorSupport::Ansi256
+                    };
+                }
+                "vscode" | "WezTerm" | "Hyper" => return ColorSupport::TrueColor,
+                "Apple_Terminal" => return ColorSupport::Ansi256,
+                _ => {}
+            }
+        }
+
+        if term.ends_with("256color") || term.ends_with("256") {
+            return ColorSupport::Ansi256;
+        }
+
+        const ANSI16_TERMS: [&str; 9] = [
+            "linux", "xterm", "screen", "tmux", "vt100", "vt220", "rxvt", "ansi", "cygwin",
+        ];
+
+        if ANSI16_TERMS.iter().any(|prefix| term.starts_with(prefix))
+            || term.contains("color")
+            || var("COLORTERM").is_some()
+        {
+            return ColorSupport::Ansi16;
+        }
+
+        ColorSupport::None
+    }
+
+    /// Returns the color depth matching this support level.
+    ///
+    /// Returns `None` for `ColorSupport::None`.
+    pub fn depth(self) -> Option<ColorDepth> {
+        match self {
+            ColorSupport::None => None,
+            ColorSupport::Ansi16 => Some(ColorDepth::Ansi16),
+            ColorSupport::Ansi256 => Some(ColorDepth::Ansi256),
+            ColorSupport::TrueColor => Some(ColorDepth::TrueColor),
+        }
+    }
+
+    /// Returns the number of colors available with this support level.
+    pub fn color_count(self) -> u32 {
+        match self {
+            ColorSupport::None => 0,
+            ColorSupport::Ansi16 => 16,
+            ColorSupport::Ansi256 => 256,
+            ColorSupport::TrueColor => 1 << 24,
+        }
+    }
+}
+
+/// Returns the color support of the CI services known to render colors in their logs.
+fn ci_color_support<F>(var: &F) -> Option<ColorSupport>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    if var("GITHUB_ACTIONS").is_some() || var("GITEA_ACTIONS").is_some() {
+        return Some(ColorSupport::TrueColor);
+    }
+
+    const CI_SERVICES: [&str; 7] = [
+        "GITLAB_CI",
+        "TRAVIS",
+        "CIRCLECI",
+        "APPVEYOR",
+        "BUILDKITE",
+        "DRONE",
+        "TF_BUILD",
+    ];
+
+    if CI_SERVICES.iter().any(|name| var(name).is_some()) {
+        return Some(ColorSupport::Ansi16);
+    }
+
+    None
+}
+
+/// Returns the color support from the environment, or from the `console` function for the
+/// terminals without the `TERM` variable.
+fn with_console_fallback<F, C>(var: F, is_tty: bool, console: C) -> ColorSupport
+where
+    F: Fn(&str) -> Option<String>,
+    C: FnOnce() -> Option<ColorSupport>,
+{
+    let support = ColorSupport::from_env(&var, is_tty);
+
+    if support == ColorSupport::None && is_tty && var("TERM").is_none() {
+        return console().unwrap_or(support);
+    }
+
+    support
+}
+
+/// Returns the color support of the Windows console.
+#[cfg(windows)]
+fn console_color_support() -> Option<ColorSupport> {
+    if crossterm_utils::supports_ansi() {
+        Some(ColorSupport::TrueColor)
+    } else {
+        Some(ColorSupport::Ansi16)
+    }
+}
+
+/// Returns the color support of the Windows console, there's none on UNIX systems.
+#[cfg(unix)]
+fn console_color_support() -> Option<ColorSupport> {
+    None
+}
+
+/// Returns `true` if the standard output is a terminal.
+#[cfg(unix)]
+pub(crate) fn is_stdout_tty() -> bool {
+    unsafe { libc::isatty(libc::STDOUT_FILENO) == 1 }
+}
+
+/// Returns `true` if the standard output is a terminal.
+#[cfg(windows)]
+pub(crate) fn is_stdout_tty() -> bool {
+    use crossterm_winapi::{ConsoleMode, Handle, HandleType};
+
+    Handle::new(HandleType::OutputHandle)
+        .and_then(|handle| ConsoleMode::from(handle).mode())
+        .is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::ColorSupport;
+
+    use super::with_console_fallback;
+
+    fn detect(vars: &[(&str, &str)], is_tty: bool) -> ColorSupport {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+
+        ColorSupport::from_env(|key| vars.get(key).cloned(), is_tty)
+    }
+
+    #[test]
+    fn test_dumb_terminal() {
+        assert_eq!(detect(&[("TERM", "dumb")], true), ColorSupport::None);
+        assert_eq!(
+            detect(&[("TERM", "dumb"), ("COLORTERM", "truecolor")], true),
+            ColorSupport::None
+        );
+    }
+
+    #[test]
+    fn test_windows_console_fallback() {
+        let console = || Some(ColorSupport::TrueColor);
+
+        assert_eq!(
+            with_console_fallback(|_| None, true, console),
+            ColorSupport::TrueColor
+        );
+        assert_eq!(
+            with_console_fallback(|_| None, false, console),
+            ColorSupport::None
+        );
+        assert_eq!(
+            with_console_fallback(
+                |key| if key == "TERM" {
+                    Some("foo".to_string())
+                } else {
+                    None
+                },
+                true,
+                console
+            ),
+            ColorSupport::None
+        );
+        assert_eq!(
+            with_console_fallback(|_| None, true, || None),
+            ColorSupport::None
+        );
+    }
+
+    #[test]
+    fn test_unknown_terminal() {
+        assert_eq!(detect(&[], true), ColorSupport::None);
+        assert_eq!(detect(&[("TERM", "foo")], true), ColorSupport::None);
+    }
+
+    #[test]
+    fn test_not_a_tty() {
+        assert_eq!(
+            detect(&[("TERM", "xterm-256color")], false),
+            ColorSupport::None
+        );
+    }
+
+    #[test]
+    fn test_ci_services() {
+        assert_eq!(
+            detect(&[("CI", "true"), ("GITHUB_ACTIONS", "true")], false),
+            ColorSupport::TrueColor
+        );
+        assert_eq!(
+            detect(&[("CI", "true"), ("TRAVIS", "true")], false),
+            ColorSupport::Ansi16
+        );
+        assert_eq!(detect(&[("CI", "true")], false), ColorSupport::None);
+    }
+
+    #[test]
+    fn test_true_color() {
+        assert_eq!(
+            detect(&[("TERM", "xterm"), ("COLORTERM", "truecolor")], true),
+            ColorSupport::TrueColor
+        );
+        assert_eq!(
+            detect(&[("COLORTERM", "24bit")], true),
+            ColorSupport::TrueColor
+        );
+        assert_eq!(
+            detect(&[("TERM", "xterm-direct")], true),
+            ColorSupport::TrueColor
+        );
+    }
+
+    #[test]
+    fn test_term_program() {
+        assert_eq!(
+            detect(
+                &[
+                    ("TERM", "xterm-256color"),
+                    ("TERM_PROGRAM", "iTerm.app"),
+                    ("TERM_PROGRAM_VERSION", "3.3.6")
+                ],
+                true
+            ),
+            ColorSupport::TrueColor
+        );
+        assert_eq!(
+            detect(
+                &[("TERM", "xterm"), ("TERM_PROGRAM", "Apple_Terminal")],
+                true
+            ),
+            ColorSupport::Ansi256
+        );
+    }
+
+    #[test]
+    fn test_term_families() {
+        assert_eq!(
+            detect(&[("TERM", "screen-256color")], true),
+            ColorSupport::Ansi256
+        );
+        assert_eq!(detect(&[("TERM", "linux")], true), ColorSupport::Ansi16);
+        assert_eq!(detect(&[("TERM", "xterm")], true), ColorSupport::Ansi16);
+        assert_eq!(
+            detect(&[("TERM", "foo"), ("COLORTERM", "1")], true),
+            ColorSupport::Ansi16
+        );
+    }
+}
@@ -0,0 +1,330 @@
+//! This module contains the renderer writing the styled text as an SVG image.
+
+use std::fmt::Write;
+
+use crate::html::{escape, hex};
+use crate::{Attribute, ObjectStyle, Palette, Span, StyledText};
+
+type Rgb = (u8, u8, u8);
+
+/// A renderer writing the styled text as an SVG image, e.g. a screenshot for the documentation.
+///
+/// The text is laid out on a grid of cells with a monospace font, a wide character takes two
+/// cells. Each span is written as a `<text>` element with its foreground color and a `<rect>`
+/// element with its background color. The named colors are turned into RGB values with the
+/// [`Palette`](struct.Palette.html).
+///
+/// The `Bold`, `Italic`, `Underlined`, `Dim`, `CrossedOut` & `Hidden` attributes are supported,
+/// `Reverse` swaps the colors.
+///
+/// # Examples
+///
+/// ```
+/// use crossterm_style::{Colorize, Palette, StyledText, Svg};
+///
+/// let text = StyledText::from("$ ".green()) + "cargo build";
+///
+/// let svg = Svg::new().palette(Palette::vga()).render(&text);
+///
+/// assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"124\" height=\"38\""));
+/// assert!(svg.contains(">cargo build</text>"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Svg {
+    palette: Palette,
+    font_family: String,
+    font_size: u32,
+    cell_width: u32,
+    line_height: u32,
+    padding: u32,
+}
+
+impl Svg {
+    /// Creates a new `Svg` renderer with the xterm palette and a 14px monospace font on a grid
+    /// of 8x18 pixels cells.
+    pub fn new() -> Svg {
+        Svg {
+            palette: Palette::xterm(),
+            font_family: "monospace".to_string(),
+            font_size: 14,
+            cell_width: 8,
+            line_height: 18,
+            padding: 10,
+        }
+    }
+
+    /// Sets the palette used to turn the colors into RGB values.
+    pub fn palette(mut self, palette: Palette) -> Svg {
+        self.palette = palette;
+        self
+    }
+
+    /// Sets the font family and the font size in pixels.
+    pub fn font(mut self, family: &str, size: u32) -> Svg {
+        self.font_family = family.to_string();
+        self.font_size = size;
+        self
+    }
+
+    /// Sets the size of a cell (a column of a line) in pixels.
+    pub fn cell_size(mut self, width: u32, height: u32) -> Svg {
+        self.cell_width = width;
+        self.line_height = height;
+        self
+    }
+
+    /// Sets the padding around the text in pixels.
+    pub fn padding(mut self, padding: u32) -> Svg {
+        self.padding = padding;
+        self
+    }
+
+    /// Returns the text written as an SVG image.
+    pub fn render(&self, text: &StyledText) -> String {
+        let lines = lines(text);
+
+        let columns = lines
+            .iter()
+            .map(|line| line.iter().map(Span::width).sum())
+            .max()
+            .unwrap_or(0);
+
+        let mut backgrounds = String::new();
+        let mut texts = String::new();
+
+        for (row, line) in lines.iter().enumerate() {
+            let y = self.padding + row as u32 * self.line_height;
+            let mut column = 0;
+
+            for span in line {
+                let width = span.width() as u32;
+                let x = self.padding + column * self.cell_width;
+                column += width;
+
+                let (fg, bg) = self.colors(&span.style);
+
+                if let Some(bg) = bg {
+                    let _ = writeln!(
+                        backgrounds,
+                        "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+                        x,
+                        y,
+                        width * self.cell_width,
+                        self.line_height,
+                        hex(bg)
+                    );
+                }
+
+                if span.content.trim().is_empty() || span.style.attrs.contains(Attribute::Hidden) {
+                    continue;
+                }
+
+                let _ = write!(
+                    texts,
+                    "<text x=\"{}\" y=\"{}\" fill=\"{}\" textLength=\"{}\" \
+                     lengthAdjust=\"spacingAndGlyphs\"{}>",
+                    x,
+                    y + self.baseline(),
+                    hex(fg),
+                    width * self.cell_width,
+                    text_attributes(&span.style)
+                );
+                escape(&mut texts, &span.content);
+                texts.push_str("</text>\n");
+            }
+        }
+
+        let mut svg = String::new();
+
+        let _ = writeln!(
+            svg,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" \
+             font-family=\"{}\" font-size=\"{}\" xml:space=\"preserve\">",
+            2 * self.padding + columns as u32 * self.cell_width,
+            2 * self.padding + lines.len() as u32 * self.line_height,
+            escape_to_string(&self.font_family),
+            self.font_size
+        );
+        let _ = writeln!(
+            svg,
+            "<rect width=\"100%\" height=\"100%\" fill=\"{}\"/>",
+            hex(self.palette.background)
+        );
+        svg.push_str(&backgrounds);
+        svg.push_str(&texts);
+        svg.push_str("</svg>\n");
+
+        svg
+    }
+
+    /// Returns the input containing ANSI escape sequences written as an SVG image.
+    ///
+    /// See [`parse_ansi`](fn.parse_ansi.html) for more info.
+    pub fn render_ansi(&self, input: &str) -> String {
+        self.render(&StyledText::from_ansi(input))
+    }
+
+    /// Returns the offset of the text baseline from the top of the line, the text is centered
+    /// vertically in the line.
+    fn baseline(&self) -> u32 {
+        // the ascent of the monospace fonts is about 80% of the font size
+        self.line_height.saturating_sub(self.font_size) / 2 + self.font_size * 4 / 5
+    }
+
+    /// Returns the RGB values of the foreground color and of the background color, if it's not
+    /// the default one.
+    fn colors(&self, style: &ObjectStyle) -> (Rgb, Option<Rgb>) {
+        let fg = style.fg_color.and_then(|color| self.palette.rgb(color));
+        let bg = style.bg_color.and_then(|color| self.palette.rgb(color));
+
+        if style.attrs.contains(Attribute::Reverse) {
+            (
+                bg.unwrap_or(self.palette.background),
+                Some(fg.unwrap_or(self.palette.foreground)),
+            )
+        } else {
+            (fg.unwrap_or(self.palette.foreground), bg)
+        }
+    }
+}
+
+impl Default for Svg {
+    fn default() -> Self {
+        Svg::new()
+    }
+}
+
+/// Splits the spans at the line breaks, the escape sequences nested in the spans are parsed.
+fn lines(text: &StyledText) -> Vec<Vec<Span>> {
+    let mut lines = vec![Vec::new()];
+
+    for (style, content) in text.pieces() {
+        for (index, content) in content.split('\n').enumerate() {
+            if index > 0 {
+                lines.push(Vec::new());
+            }
+
+            let content = content.trim_end_matches('\r');
+            if !content.is_empty() {
+                // safe unwrap, there is always a line
+                lines.last_mut().unwrap().push(Span::new(content, style));
+            }
+        }
+    }
+
+    if lines.len() > 1 && lines.last().is_some_and(Vec::is_empty) {
+        lines.pop();
+    }
+
+    lines
+}
+
+/// Returns the SVG attributes of the text attributes.
+fn text_attributes(style: &ObjectStyle) -> String {
+    let mut attributes = String::new();
+
+    if style.attrs.contains(Attribute::Bold) {
+        attributes.push_str(" font-weight=\"bold\"");
+    }
+    if style.attrs.contains(Attribute::Italic) {
+        attributes.push_str(" font-style=\"italic\"");
+    }
+    if style.attrs.contains(Attribute::Dim) {
+        attributes.push_str(" opacity=\"0.5\"");
+    }
+
+    let mut decorations = Vec::new();
+    if style.attrs.contains(Attribute::Underlined) {
+        decorations.push("underline");
+    }
+    if style.attrs.contains(Attribute::CrossedOut) {
+        decorations.push("line-through");
+    }
+    if !decorations.is_empty() {
+        let _ = write!(attributes, " text-decoration=\"{}\"", decorations.join(" "));
+    }
+
+    attributes
+}
+
+fn escape_to_string(text: &str) -> String {
+    let mut escaped = String::new();
+    escape(&mut escaped, text);
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Color, Colorize, ObjectStyle, Span, StyledText, Styler, Svg};
+
+    fn elements(svg: &str) -> Vec<&str> {
+        svg.lines().skip(2).collect()
+    }
+
+    #[test]
+    fn test_size() {
+        let svg = Svg::new().render(&StyledText::from("ab\n日本語\n"));
+
+        assert!(svg.starts_with(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"68\" height=\"56\" \
+             font-family=\"monospace\" font-size=\"14\" xml:space=\"preserve\">\n\
+             <rect width=\"100%\" height=\"100%\" fill=\"#000000\"/>\n"
+        ));
+    }
+
+    #[test]
+    fn test_spans() {
+        let text = StyledText::from("a".red().bold()) + " " + "bc".italic().underlined();
+        let svg = Svg::new().padding(0).cell_size(10, 20).render(&text);
+
+        assert_eq!(
+            elements(&svg),
+            vec![
+                "<text x=\"0\" y=\"14\" fill=\"#ff0000\" textLength=\"10\" \
+                 lengthAdjust=\"spacingAndGlyphs\" font-weight=\"bold\">a</text>",
+                "<text x=\"20\" y=\"14\" fill=\"#e5e5e5\" textLength=\"20\" \
+                 lengthAdjust=\"spacingAndGlyphs\" font-style=\"italic\" \
+                 text-decoration=\"underline\">bc</text>",
+                "</svg>"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_nested_escape_sequences() {
+        let span = Span::new("a\x1B[1mb\x1B[22m", ObjectStyle::new().fg(Color::Red));
+        let svg = Svg::new().padding(0).cell_size(10, 20).render(&span.into());
+
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"20\" "));
+        assert_eq!(
+            elements(&svg),
+            vec![
+                "<text x=\"0\" y=\"14\" fill=\"#ff0000\" textLength=\"10\" \
+                 lengthAdjust=\"spacingAndGlyphs\">a</text>",
+                "<text x=\"10\" y=\"14\" fill=\"#ff0000\" textLength=\"10\" \
+                 lengthAdjust=\"spacingAndGlyphs\" font-weight=\"bold\">b</text>",
+                "</svg>"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_backgrounds_and_reverse() {
+        let style = ObjectStyle::new().bg(Color::Rgb { r: 1, g: 2, b: 3 });
+        let text = StyledText::from(Span::new("a", style)) + "\n" + "<b>".reverse();
+        let svg = Svg::new().padding(0).cell_size(10, 20).render(&text);
+
+        assert_eq!(
+            elements(&svg),
+            vec![
+                "<rect x=\"0\" y=\"0\" width=\"10\" height=\"20\" fill=\"#010203\"/>",
+                "<rect x=\"0\" y=\"20\" width=\"30\" height=\"20\" fill=\"#e5e5e5\"/>",
+                "<text x=\"0\" y=\"14\" fill=\"#e5e5e5\" textLength=\"10\" \
+                 lengthAdjust=\"spacingAndGlyphs\">a</text>",
+                "<text x=\"0\" y=\"34\" fill=\"#000000\" textLength=\"30\" \
+                 lengthAdjust=\"spacingAndGlyphs\">&lt;b&gt;</text>",
+                "</svg>"
+            ]
+        );
+    }
+}
@@ -0,0 +1,770 @@
+//! This module contains the styled text composed of several styled spans.
+
+use std::fmt::{self, Display, Formatter};
+use std::iter::FromIterator;
+use std::ops::{Add, AddAssign};
+use std::slice;
+use std::vec;
+
+use crossterm_utils::queue;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use crate::styledobject::{enclosing_style, ChangeStyle};
+use crate::{ansi_width, should_colorize, AnsiSpans, ObjectStyle, StyledObject};
+
+/// A piece of text with a single style.
+///
+/// # Examples
+///
+/// ```
+/// use crossterm_style::{Color, ObjectStyle, Span};
+///
+/// let span = Span::new("error", ObjectStyle::new().fg(Color::Red));
+///
+/// assert_eq!(span.content, "error");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Span {
+    /// The style of the content.
+    pub style: ObjectStyle,
+    /// The text.
+    pub content: String,
+}
+
+impl Span {
+    /// Creates a new `Span` with the given style.
+    pub fn new<S: Into<String>>(content: S, style: ObjectStyle) -> Span {
+        Span {
+            style,
+            content: content.into(),
+        }
+    }
+
+    /// Creates a new `Span` without any style.
+    pub fn raw<S: Into<String>>(content: S) -> Span {
+        Span::new(content, ObjectStyle::new())
+    }
+
+    /// Returns the number of columns taken by the content on the terminal.
+    ///
+    /// The escape sequences in the content take no columns, see
+    /// [`ansi_width`](fn.ansi_width.html) for more info.
+    pub fn width(&self) -> usize {
+        ansi_width(&self.content)
+    }
+}
+
+impl From<&str> for Span {
+    fn from(content: &str) -> Self {
+        Span::raw(content)
+    }
+}
+
+impl From<String> for Span {
+    fn from(content: String) -> Self {
+        Span::raw(content)
+    }
+}
+
+/// The content is written to a `String`, a `StyledObject` nested in the content is written
+/// with its escape sequences.
+impl<D: Display + Clone> From<StyledObject<D>> for Span {
+    fn from(styled: StyledObject<D>) -> Self {
+        Span::new(styled.content.to_string(), styled.object_style)
+    }
+}
+
+impl Display for Span {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.style.apply_to(self.content.as_str()), f)
+    }
+}
+
+/// A text composed of several styled spans.
+///
+/// The text is written with a single SGR sequence between two spans, changing only what
+/// differs between their styles (see [`sgr_diff`](fn.sgr_diff.html)). The styles of the spans
+/// are applied on top of the style of the enclosing [`StyledObject`](struct.StyledObject.html),
+/// if any, which is restored at the end of the text.
+///
+/// # Examples
+///
+/// ```
+/// use crossterm_style::{Colorize, Span, StyledText, Styler};
+///
+/// let mut status = StyledText::new();
+/// status.push("ok".green().bold());
+/// status.push(" 3 passed, ");
+/// status.push("1 failed".red());
+///
+/// assert_eq!(status.len(), 3);
+/// assert_eq!(status.plain(), "ok 3 passed, 1 failed");
+///
+/// println!("{}", status);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct StyledText {
+    spans: Vec<Span>,
+}
+
+impl StyledText {
+    /// Creates a new empty `StyledText`.
+    pub fn new() -> StyledText {
+        StyledText::default()
+    }
+
+    /// Parses the text from a string containing ANSI escape sequences.
+    ///
+    /// See [`parse_ansi`](fn.parse_ansi.html) for more info.
+    pub fn from_ansi(input: &str) -> StyledText {
+        AnsiSpans::new(input)
+            .map(|(style, content)| Span::new(content, style))
+            .collect()
+    }
+
+    /// Appends a span to the end of the text.
+    pub fn push<S: Into<Span>>(&mut self, span: S) {
+        self.spans.push(span.into());
+    }
+
+    /// Appends the spans of `other` to the end of the text.
+    pub fn append(&mut self, mut other: StyledText) {
+        self.spans.append(&mut other.spans);
+    }
+
+    /// Returns the spans.
+    pub fn spans(&self) -> &[Span] {
+        &self.spans
+    }
+
+    /// Returns the mutable spans.
+    pub fn spans_mut(&mut self) -> &mut Vec<Span> {
+        &mut self.spans
+    }
+
+    /// Returns an iterator over the spans.
+    pub fn iter(&self) -> slice::Iter<'_, Span> {
+        self.spans.iter()
+    }
+
+    /// Returns the number of spans.
+    pub fn len(&self) -> usize {
+        self.spans.len()
+    }
+
+    /// Returns `true` if the text has no spans.
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    /// Returns the number of columns taken by the text on the terminal.
+    ///
+    /// See [`ansi_width`](fn.ansi_width.html) for the width of the characters.
+    pub fn width(&self) -> usize {
+        self.spans.iter().map(Span::width).sum()
+    }
+
+    /// Returns the text without the styles.
+    pub fn plain(&self) -> String {
+        self.spans
+            .iter()
+            .map(|span| span.content.as_str())
+            .collect()
+    }
+
+    /// Returns the text cut to `width` columns, ending with `ellipsis` if it was cut.
+    ///
+    /// The ellipsis has the style of the span that was cut, use
+    /// [`truncate_with`](#method.truncate_with) to style it. The text is never cut inside a
+    /// grapheme cluster, a wide character which doesn't fit is dropped entirely, so the
+    /// result can be narrower than `width`. The ellipsis is dropped if it doesn't fit. The
+    /// escape sequences nested in the spans are parsed into spans, they're never cut.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossterm_style::{Colorize, StyledText};
+    ///
+    /// let path = StyledText::from("/home/user/".blue()) + "documents/report.txt";
+    ///
+    /// assert_eq!(path.truncate(14, "…").plain(), "/home/user/do…");
+    /// assert_eq!(path.truncate(40, "…"), path);
+    /// ```
+    pub fn truncate(&self, width: usize, ellipsis: &str) -> StyledText {
+        self.truncate_inner(width, ellipsis, None)
+    }
+
+    /// Returns the text cut to `width` columns, ending with the styled `ellipsis` if it was
+    /// cut.
+    ///
+    /// The style of the ellipsis is applied on top of the style of the span that was cut. See
+    /// [`truncate`](#method.truncate) for more info.
+    pub fn truncate_with(&self, width: usize, ellipsis: Span) -> StyledText {
+        self.truncate_inner(width, &ellipsis.content, Some(ellipsis.style))
+    }
+
+    fn truncate_inner(
+        &self,
+        width: usize,
+        ellipsis: &str,
+        ellipsis_style: Option<ObjectStyle>,
+    ) -> StyledText {
+        if self.width() <= width {
+            return self.clone();
+        }
+
+        let ellipsis = if ellipsis.width() <= width {
+            ellipsis
+        } else {
+            ""
+        };
+        let available = width - ellipsis.width();
+
+        let mut truncated = StyledText::new();
+        let mut used = 0;
+
+        for (style, content) in self.pieces() {
+            let mut end = 0;
+
+            for (index, grapheme) in content.grapheme_indices(true) {
+                if used + grapheme.width() > available {
+                    break;
+                }
+                used += grapheme.width();
+                end = index + grapheme.len();
+            }
+
+            if end > 0 {
+                truncated.push(Span::new(&content[..end], style));
+            }
+
+            if end < content.len() {
+                if !ellipsis.is_empty() {
+                    let style = match ellipsis_style {
+                        Some(ellipsis_style) => style.merge(ellipsis_style),
+                        None => style,
+                    };
+                    truncated.push(Span::new(ellipsis, style));
+                }
+                break;
+            }
+        }
+
+        truncated
+    }
+
+    /// Returns the styled pieces of plain text of the spans.
+    ///
+    /// The escape sequences nested in the content of the spans are parsed, their styles are
+    /// applied on top of the style of the span (see
+    /// [`AnsiSpans`](struct.AnsiSpans.html)). The text can then be cut anywhere without
+    /// breaking a sequence.
+    pub(crate) fn pieces(&self) -> impl Iterator<Item = (ObjectStyle, &str)> {
+        self.spans.iter().flat_map(|span| {
+            AnsiSpans::new(&span.content)
+                .map(move |(style, content)| (span.style.merge(style), content))
+        })
+    }
+
+    /// Wraps the text to lines of at most `width` columns.
+    ///
+    /// The lines are broken at the whitespace between the words, the words longer than the
+    /// width are broken between two grapheme clusters. The whitespace at the place of the
+    /// break is removed. The line breaks (`\n`) of the text and the indentation at the start
+    /// of the paragraphs are kept. The escape sequences nested in the spans are parsed into
+    /// spans, they're never split across lines.
+    ///
+    /// Each line is a `StyledText` with the styles of the spans, so a line written to the
+    /// terminal opens the style of its first span and resets the style at its end. The style
+    /// doesn't bleed into the margin nor gets lost on the next line.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use crossterm_style::{Colorize, StyledText};
+    ///
+    /// let text = StyledText::from("error:".red()) + " the file was not found";
+    /// let lines: Vec<String> = text.wrap(12).iter().map(StyledText::plain).collect();
+    ///
+    /// assert_eq!(lines, vec!["error: the", "file was not", "found"]);
+    /// ```
+    pub fn wrap(&self, width: usize) -> Vec<StyledText> {
+        let mut wrapper = Wrapper::new(width);
+
+        for (style, content) in self.pieces() {
+            for grapheme in content.graphemes(true) {
+                wrapper.push(grapheme, style);
+            }
+        }
+
+        wrapper.finish()
+    }
+}
+
+/// Wraps the styled graphemes to lines.
+struct Wrapper<'a> {
+    width: usize,
+    lines: Vec<StyledText>,
+    line: StyledText,
+    line_width: usize,
+    /// The whitespace since the last word.
+    spaces: Vec<(&'a str, ObjectStyle)>,
+    /// The word being read.
+    word: Vec<(&'a str, ObjectStyle)>,
+}
+
+impl<'a> Wrapper<'a> {
+    fn new(width: usize) -> Wrapper<'a> {
+        Wrapper {
+            width,
+            lines: Vec::new(),
+            line: StyledText::new(),
+            line_width: 0,
+            spaces: Vec::new(),
+            word: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, grapheme: &'a str, style: ObjectStyle) {
+        if grapheme == "\n" || grapheme == "\r\n" {
+            self.place_word();
+            self.spaces.clear();
+            self.break_line();
+        } else if grapheme.chars().all(char::is_whitespace) {
+            self.place_word();
+            self.spaces.push((grapheme, style));
+        } else {
+            self.word.push((grapheme, style));
+        }
+    }
+
+    fn finish(mut self) -> Vec<StyledText> {
+        self.place_word();
+
+        if !self.line.is_empty() {
+            self.break_line();
+        }
+
+        self.lines
+    }
+
+    /// Places the word read and the whitespace before it on the current line or the next ones.
+    fn place_word(&mut self) {
+        if self.word.is_empty() {
+            return;
+        }
+
+        let spaces_width: usize = self.spaces.iter().map(|(space, _)| space.width()).sum();
+        let word_width: usize = self.word.iter().map(|(grapheme, _)| grapheme.width()).sum();
+
+        if self.line_width + spaces_width + word_width <= self.width {
+            self.append_all(true);
+            return;
+        }
+
+        // the indentation at the start of a paragraph is kept
+        let indented = self.line.is_empty();
+
+        if !self.line.is_empty() {
+            self.break_line();
+        }
+
+        if word_width <= self.width {
+            self.append_all(false);
+            return;
+        }
+
+        // the word doesn't fit on a line
+        let spaces = std::mem::take(&mut self.spaces);
+        let word = std::mem::take(&mut self.word);
+
+        let spaces = spaces.into_iter().filter(|_| indented);
+
+        for (grapheme, style) in spaces.chain(word) {
+            if self.line_width + grapheme.width() > self.width && !self.line.is_empty() {
+                self.break_line();
+            }
+            self.append(grapheme, style);
+        }
+    }
+
+    /// Appends the word read, and the whitespace before it if `with_spaces` is `true`.
+    fn append_all(&mut self, with_spaces: bool) {
+        let spaces = std::mem::take(&mut self.spaces);
+        let word = std::mem::take(&mut self.word);
+
+        let spaces = spaces.into_iter().filter(|_| with_spaces);
+
+        for (grapheme, style) in spaces.chain(word) {
+            self.append(grapheme, style);
+        }
+    }
+
+    /// Appends a grapheme to the current line, merging it with the last span if the style is
+    /// the same.
+    fn append(&mut self, grapheme: &str, style: ObjectStyle) {
+        match self.line.spans.last_mut() {
+            Some(span) if span.style == style => span.content.push_str(grapheme),
+            _ => self.line.push(Span::new(grapheme, style)),
+        }
+        self.line_width += grapheme.width();
+    }
+
+    fn break_line(&mut self) {
+        self.lines.push(std::mem::take(&mut self.line));
+        self.line_width = 0;
+    }
+}
+
+impl Display for StyledText {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if !should_colorize() {
+            for span in &self.spans {
+                f.write_str(&span.content)?;
+            }
+            return Ok(());
+        }
+
+        let outer_style = enclosing_style().unwrap_or_default();
+        let mut current = outer_style;
+
+        for span in &self.spans {
+            let style = outer_style.merge(span.style);
+
+            queue!(
+                f,
+                ChangeStyle {
+                    from: current,
+                    to: style
+                }
+            )
+            .map_err(|_| fmt::Error)?;
+            f.write_str(&span.content)?;
+
+            current = style;
+        }
+
+        queue!(
+            f,
+            ChangeStyle {
+                from: current,
+                to: outer_style
+            }
+        )
+        .map_err(|_| fmt::Error)
+    }
+}
+
+impl From<Span> for StyledText {
+    fn from(span: Span) -> Self {
+        StyledText { spans: vec![span] }
+    }
+}
+
+impl From<Vec<Span>> for StyledText {
+    fn from(spans: Vec<Span>) -> Self {
+        StyledText { spans }
+    }
+}
+
+impl From<&str> for StyledText {
+    fn from(content: &str) -> Self {
+        Span::from(content).into()
+    }
+}
+
+impl From<String> for StyledText {
+    fn from(content: String) -> Self {
+        Span::from(content).into()
+    }
+}
+
+/// The content is written to a `String` and parsed, a `StyledObject` nested in the content
+/// becomes a span with the merged styles.
+impl<D: Display + Clone> From<StyledObject<D>> for StyledText {
+    fn from(styled: StyledObject<D>) -> Self {
+        AnsiSpans::new(&styled.content.to_string())
+            .map(|(style, content)| Span::new(content, styled.object_style.merge(style)))
+            .collect()
+    }
+}
+
+impl<S: Into<Span>> FromIterator<S> for StyledText {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        StyledText {
+            spans: iter.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl<S: Into<Span>> Extend<S> for StyledText {
+    fn extend<I: IntoIterator<Item = S>>(&mut self, iter: I) {
+        self.spans.extend(iter.into_iter().map(Into::into));
+    }
+}
+
+impl IntoIterator for StyledText {
+    type Item = Span;
+    type IntoIter = vec::IntoIter<Span>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.spans.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a StyledText {
+    type Item = &'a Span;
+    type IntoIter = slice::Iter<'a, Span>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.spans.iter()
+    }
+}
+
+impl<S: Into<StyledText>> Add<S> for StyledText {
+    type Output = StyledText;
+
+    fn add(mut self, other: S) -> Self::Output {
+        self.append(other.into());
+        self
+    }
+}
+
+impl<S: Into<StyledText>> AddAssign<S> for StyledText {
+    fn add_assign(&mut self, other: S) {
+        self.append(other.into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        set_color_choice, style, Attribute, Color, ColorChoice, Colorize, ObjectStyle, Span,
+        StyledText, Styler,
+    };
+
+    #[test]
+    fn test_push_and_concat() {
+        let mut text = StyledText::new();
+        text.push("a".red());
+        text.push("b");
+
+        let text = text + "c".blue() + StyledText::from("d");
+
+        assert_eq!(text.len(), 4);
+        assert_eq!(text.plain(), "abcd");
+        assert_eq!(
+            text.spans()[0],
+            Span::new("a", ObjectStyle::new().fg(Color::Red))
+        );
+        assert_eq!(text.spans()[1], Span::raw("b"));
+    }
+
+    #[test]
+    fn test_collect_and_iterate() {
+        let text: StyledText = vec!["a".red(), "b".green()].into_iter().collect();
+
+        let colors: Vec<_> = text.iter().map(|span| span.style.fg_color).collect();
+        assert_eq!(colors, vec![Some(Color::Red), Some(Color::Green)]);
+
+        let contents: Vec<String> = text.into_iter().map(|span| span.content).collect();
+        assert_eq!(contents, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_display_minimal_transitions() {
+        set_color_choice(ColorChoice::Always);
+
+        let mut text = StyledText::new();
+        text.push("a".red().bold());
+        text.push("b".red());
+        text.push("c");
+
+        assert_eq!(text.to_string(), "\x1B[1;38;5;9ma\x1B[22mb\x1B[0mc");
+    }
+
+    #[test]
+    fn test_display_resets_at_the_end() {
+        set_color_choice(ColorChoice::Always);
+
+        let text = StyledText::from("a".red()) + "b".underlined();
+
+        assert_eq!(text.to_string(), "\x1B[38;5;9ma\x1B[0;4mb\x1B[0m");
+    }
+
+    #[test]
+    fn test_display_nested_in_styled_object() {
+        set_color_choice(ColorChoice::Always);
+
+        let text = StyledText::from("a") + "b".red();
+
+        assert_eq!(
+            style(text).attr(Attribute::Bold).to_string(),
+            "\x1B[1ma\x1B[38;5;9mb\x1B[39m\x1B[0m"
+        );
+    }
+
+    #[test]
+    fn test_width() {
+        let text = StyledText::from("日本".red()) + " " + "\u{1F600}".bold();
+
+        assert_eq!(text.spans()[0].width(), 4);
+        assert_eq!(text.width(), 7);
+    }
+
+    #[test]
+    fn test_width_with_escape_sequences() {
+        set_color_choice(ColorChoice::Always);
+
+        let span = Span::from(style(format!("a{}c", "b".bold())).with(Color::Red));
+        let text = StyledText::from(span.clone()) + Span::raw("\x1B]0;title\x07d");
+
+        assert!(span.content.contains('\x1B'));
+        assert_eq!(span.width(), 3);
+        assert_eq!(text.width(), 4);
+    }
+
+    #[test]
+    fn test_truncate() {
+        let text = StyledText::from("ab".red()) + "cd" + "ef".blue();
+
+        assert_eq!(text.truncate(6, "…"), text);
+        assert_eq!(
+            text.truncate(4, "…"),
+            StyledText::from("ab".red()) + "c" + Span::raw("…")
+        );
+        assert_eq!(
+            text.truncate(2, "…"),
+            StyledText::from("a".red()) + Span::new("…", ObjectStyle::new().fg(Color::Red))
+        );
+        assert_eq!(text.truncate(4, ""), StyledText::from("ab".red()) + "cd");
+        assert_eq!(text.truncate(1, "..."), StyledText::from("a".red()));
+    }
+
+    #[test]
+    fn test_truncate_nested_styled_object() {
+        set_color_choice(ColorChoice::Always);
+
+        let text = StyledText::from(Span::from(
+            style(format!("{} text", "bold".bold())).with(Color::Red),
+        ));
+        let truncated = text.truncate(3, "…");
+
+        assert_eq!(
+            truncated,
+            StyledText::from("bo".red().bold()) + "…".red().bold()
+        );
+        assert_eq!(
+            text.truncate(7, ""),
+            StyledText::from("bold".red().bold()) + " te".red()
+        );
+    }
+
+    #[test]
+    fn test_truncate_graphemes() {
+        let text = StyledText::from("e\u{301}日本");
+
+        assert_eq!(text.truncate(3, "").plain(), "e\u{301}日");
+        assert_eq!(text.truncate(2, "").plain(), "e\u{301}");
+        assert_eq!(text.truncate(3, "~").plain(), "e\u{301}~");
+    }
+
+    #[test]
+    fn test_truncate_with_styled_ellipsis() {
+        let text = StyledText::from("abc".red());
+
+        assert_eq!(
+            text.truncate_with(2, "…".bold().into()),
+            StyledText::from("a".red()) + "…".red().bold()
+        );
+    }
+
+    #[test]
+    fn test_from_nested_styled_object() {
+        set_color_choice(ColorChoice::Always);
+
+        let text = StyledText::from(style(format!("a{}", "b".bold())).with(Color::Red));
+
+        assert_eq!(text, StyledText::from("a".red()) + "b".red().bold());
+    }
+
+    #[test]
+    fn test_wrap() {
+        let text = StyledText::from("aa bb".red()) + " cc  dd";
+
+        assert_eq!(
+            text.wrap(5),
+            vec![
+                StyledText::from("aa bb".red()),
+                StyledText::from("cc"),
+                StyledText::from("dd"),
+            ]
+        );
+        assert_eq!(
+            text.wrap(8),
+            vec![
+                StyledText::from("aa bb".red()) + " cc",
+                StyledText::from("dd")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_wrap_nested_styled_object() {
+        set_color_choice(ColorChoice::Always);
+
+        let text = StyledText::from(Span::from(
+            style(format!("{} here", "bold words".bold())).with(Color::Red),
+        ));
+
+        assert_eq!(
+            text.wrap(5),
+            vec![
+                StyledText::from("bold".red().bold()),
+                StyledText::from("words".red().bold()),
+                StyledText::from("here".red()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_wrap_long_words_and_line_breaks() {
+        let text = StyledText::from("abc".red()) + "def\n\n  日本語";
+        let lines: Vec<String> = text.wrap(4).iter().map(StyledText::plain).collect();
+
+        assert_eq!(lines, vec!["abcd", "ef", "", "  日", "本語"]);
+        assert_eq!(text.wrap(4)[0], StyledText::from("abc".red()) + "d");
+        assert!(StyledText::new().wrap(4).is_empty());
+    }
+
+    #[test]
+    fn test_wrapped_lines_reopen_style() {
+        set_color_choice(ColorChoice::Always);
+
+        let lines: Vec<String> = StyledText::from("ab cd".red())
+            .wrap(2)
+            .iter()
+            .map(StyledText::to_string)
+            .collect();
+
+        assert_eq!(
+            lines,
+            vec!["\x1B[38;5;9mab\x1B[0m", "\x1B[38;5;9mcd\x1B[0m"]
+        );
+    }
+
+    #[test]
+    fn test_from_ansi() {
+        let text = StyledText::from_ansi("a\x1B[31mb\x1B[0mc");
+
+        assert_eq!(
+            text.spans(),
+            &[
+                Span::raw("a"),
+                Span::new("b", ObjectStyle::new().fg(Color::DarkRed)),
+                Span::raw("c")
+            ]
+        );
+    }
+}
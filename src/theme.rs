@@ -0,0 +1,264 @@
+//! This module contains the themes mapping the semantic roles to styles.
+
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+use crate::{Attribute, Background, Color, ObjectStyle, StyledObject};
+
+/// A theme mapping the role names (`error`, `warning`, `path`, ...) to styles.
+///
+/// The applications style their text by role instead of hard coding the colors, the theme
+/// decides how each role looks. Switching to another theme is then a single change.
+///
+/// # Lookup
+///
+/// The roles can be dotted (`diff.added`), a missing role falls back to its parent role
+/// (`diff`), then to the theme this one inherits from (see [`inherit`](#method.inherit)). The
+/// most specific role wins: `diff.added` of the parent theme is used before `diff` of this
+/// theme. A role missing entirely has no style.
+///
+/// # Examples
+///
+/// ```
+/// use crossterm_style::{Color, ObjectStyle, Theme};
+///
+/// let theme = Theme::dark().with("diff", ObjectStyle::new().fg(Color::Cyan));
+///
+/// println!("{}", theme.apply("error", "file not found"));
+///
+/// assert_eq!(theme.style("diff.renamed").fg_color, Some(Color::Cyan));
+/// assert_eq!(theme.style("diff.added").fg_color, Some(Color::Green));
+/// assert_eq!(theme.style("unknown"), ObjectStyle::new());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Theme {
+    styles: BTreeMap<String, ObjectStyle>,
+    parent: Option<Box<Theme>>,
+}
+
+impl Theme {
+    /// Creates a new empty `Theme`.
+    pub fn new() -> Theme {
+        Theme::default()
+    }
+
+    /// Returns a theme for the terminals with a dark background.
+    pub fn dark() -> Theme {
+        Theme::new()
+            .with("error", style(Some(Color::Red), &[Attribute::Bold]))
+            .with("warning", style(Some(Color::Yellow), &[Attribute::Bold]))
+            .with("info", style(Some(Color::Cyan), &[]))
+            .with("success", style(Some(Color::Green), &[]))
+            .with("hint", style(Some(Color::DarkGrey), &[Attribute::Italic]))
+            .with("path", style(Some(Color::Blue), &[Attribute::Underlined]))
+            .with("keyword", style(Some(Color::Magenta), &[Attribute::Bold]))
+            .with("string", style(Some(Color::Green), &[]))
+            .with("number", style(Some(Color::Cyan), &[]))
+            .with("comment", style(Some(Color::DarkGrey), &[]))
+            .with("heading", style(Some(Color::White), &[Attribute::Bold]))
+            .with("emphasis", style(None, &[Attribute::Bold]))
+            .with("link", style(Some(Color::Blue), &[Attribute::Underlined]))
+            .with("muted", style(Some(Color::DarkGrey), &[]))
+            .with("diff.added", style(Some(Color::Green), &[]))
+            .with("diff.removed", style(Some(Color::Red), &[]))
+    }
+
+    /// Returns a theme for the terminals with a light background.
+    pub fn light() -> Theme {
+        Theme::new()
+            .with("error", style(Some(Color::DarkRed), &[Attribute::Bold]))
+            .with(
+                "warning",
+                style(Some(Color::DarkYellow), &[Attribute::Bold]),
+            )
+            .with("info", style(Some(Color::DarkCyan), &[]))
+            .with("success", style(Some(Color::DarkGreen), &[]))
+            .with("hint", style(Some(Color::DarkGrey), &[Attribute::Italic]))
+            .with(
+                "path",
+                style(Some(Color::DarkBlue), &[Attribute::Underlined]),
+            )
+            .with(
+                "keyword",
+                style(Some(Color::DarkMagenta), &[Attribute::Bold]),
+            )
+            .with("string", style(Some(Color::DarkGreen), &[]))
+            .with("number", style(Some(Color::DarkCyan), &[]))
+            .with("comment", style(Some(Color::DarkGrey), &[]))
+            .with("heading", style(Some(Color::Black), &[Attribute::Bold]))
+            .with("emphasis", style(None, &[Attribute::Bold]))
+            .with(
+                "link",
+                style(Some(Color::DarkBlue), &[Attribute::Underlined]),
+            )
+            .with("muted", style(Some(Color::DarkGrey), &[]))
+            .with("diff.added", style(Some(Color::DarkGreen), &[]))
+            .with("diff.removed", style(Some(Color::DarkRed), &[]))
+    }
+
+    /// Returns the theme for the background, the dark theme if the background is unknown.
+    ///
+    /// See [`detect_background`](fn.detect_background.html) for more info.
+    pub fn for_background(background: Background) -> Theme {
+        match background {
+            Background::Light => Theme::light(),
+            Background::Dark | Background::Unknown => Theme::dark(),
+        }
+    }
+
+    /// Sets the style of the role.
+    pub fn with(mut self, role: &str, style: ObjectStyle) -> Theme {
+        self.insert(role, style);
+        self
+    }
+
+    /// Sets the theme used for the roles missing in this one.
+    pub fn inherit(mut self, parent: Theme) -> Theme {
+        self.parent = Some(Box::new(parent));
+        self
+    }
+
+    /// Returns the theme this one inherits from.
+    pub fn parent(&self) -> Option<&Theme> {
+        self.parent.as_deref()
+    }
+
+    /// Sets the style of the role, returns the previous style of the role.
+    pub fn insert(&mut self, role: &str, style: ObjectStyle) -> Option<ObjectStyle> {
+        self.styles.insert(role.to_string(), style)
+    }
+
+    /// Removes the role from this theme, returns its style.
+    pub fn remove(&mut self, role: &str) -> Option<ObjectStyle> {
+        self.styles.remove(role)
+    }
+
+    /// Returns the style of the role, with the fallbacks (see [lookup](#lookup)).
+    pub fn get(&self, role: &str) -> Option<ObjectStyle> {
+        let mut role = role;
+
+        loop {
+            if let Some(style) = self.get_exact(role) {
+                return Some(style);
+            }
+
+            match role.rfind('.') {
+                Some(index) => role = &role[..index],
+                None => return None,
+            }
+        }
+    }
+
+    /// Returns the style of the role, or an empty style if the role is missing.
+    pub fn style(&self, role: &str) -> ObjectStyle {
+        self.get(role).unwrap_or_default()
+    }
+
+    /// Creates a `StyledObject` by applying the style of the role to the given `val`.
+    pub fn apply<D: Display + Clone>(&self, role: &str, val: D) -> StyledObject<D> {
+        self.style(role).apply_to(val)
+    }
+
+    /// Returns an iterator over the roles of this theme and their styles, the inherited roles
+    /// are not included.
+    pub fn roles(&self) -> impl Iterator<Item = (&str, ObjectStyle)> {
+        self.styles
+            .iter()
+            .map(|(role, style)| (role.as_str(), *style))
+    }
+
+    /// Returns the style of the exact role, from this theme or the inherited ones.
+    fn get_exact(&self, role: &str) -> Option<ObjectStyle> {
+        self.styles.get(role).copied().or_else(|| {
+            self.parent
+                .as_ref()
+                .and_then(|parent| parent.get_exact(role))
+        })
+    }
+}
+
+fn style(fg_color: Option<Color>, attrs: &[Attribute]) -> ObjectStyle {
+    ObjectStyle {
+        fg_color,
+        bg_color: None,
+        attrs: attrs.iter().copied().collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Attribute, Background, Color, ObjectStyle, Theme};
+
+    #[test]
+    fn test_dotted_fallback() {
+        let red = ObjectStyle::new().fg(Color::Red);
+        let theme = Theme::new().with("diff", red);
+
+        assert_eq!(theme.get("diff"), Some(red));
+        assert_eq!(theme.get("diff.removed.line"), Some(red));
+        assert_eq!(theme.get("differ"), None);
+        assert_eq!(theme.get(""), None);
+        assert_eq!(theme.style("missing"), ObjectStyle::new());
+    }
+
+    #[test]
+    fn test_inheritance() {
+        let red = ObjectStyle::new().fg(Color::Red);
+        let blue = ObjectStyle::new().fg(Color::Blue);
+        let green = ObjectStyle::new().fg(Color::Green);
+
+        let parent = Theme::new().with("error", red).with("diff.added", green);
+        let theme = Theme::new()
+            .with("error", blue)
+            .with("diff", blue)
+            .inherit(parent);
+
+        assert_eq!(theme.get("error"), Some(blue));
+        assert_eq!(theme.get("diff.added"), Some(green));
+        assert_eq!(theme.get("diff.removed"), Some(blue));
+        assert_eq!(
+            theme.parent().and_then(|parent| parent.get("error")),
+            Some(red)
+        );
+        assert_eq!(theme.roles().count(), 2);
+    }
+
+    #[test]
+    fn test_for_background() {
+        assert_eq!(
+            Theme::for_background(Background::Light)
+                .style("error")
+                .fg_color,
+            Some(Color::DarkRed)
+        );
+        assert_eq!(
+            Theme::for_background(Background::Unknown)
+                .style("error")
+                .fg_color,
+            Some(Color::Red)
+        );
+    }
+
+    #[test]
+    fn test_apply() {
+        let theme = Theme::dark();
+        let styled = theme.apply("error", "failed");
+
+        assert_eq!(styled.content, "failed");
+        assert_eq!(styled.object_style.fg_color, Some(Color::Red));
+        assert!(styled.object_style.attrs.contains(Attribute::Bold));
+        assert_eq!(
+            theme.apply("missing", "text").object_style,
+            ObjectStyle::new()
+        );
+    }
+
+    #[test]
+    fn test_builtin_themes_have_the_same_roles() {
+        let dark = Theme::dark();
+        let light = Theme::light();
+
+        assert!(dark.roles().all(|(role, _)| light.get(role).is_some()));
+        assert_eq!(dark.roles().count(), light.roles().count());
+    }
+}
@@ -0,0 +1,48 @@
+//! This module contains the functions computing the display width of the styled text.
+
+use unicode_width::UnicodeWidthStr;
+
+use crate::parser::{Token, Tokens};
+
+/// Returns the number of columns taken by the input on the terminal.
+///
+/// The escape sequences are ignored and the width of the characters follows the Unicode East
+/// Asian Width rules: the CJK characters and most emojis take 2 columns, the combining and
+/// control characters take none.
+///
+/// # Examples
+///
+/// ```
+/// use crossterm_style::{ansi_width, Colorize};
+///
+/// assert_eq!(ansi_width("\x1B[1m日本\x1B[0m"), 4);
+/// assert_eq!(ansi_width(&"error".red().to_string()), 5);
+/// ```
+pub fn ansi_width(input: &str) -> usize {
+    Tokens::new(input)
+        .map(|token| match token {
+            Token::Text(text) => text.width(),
+            _ => 0,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ansi_width;
+
+    #[test]
+    fn test_ansi_width() {
+        assert_eq!(ansi_width(""), 0);
+        assert_eq!(ansi_width("abc"), 3);
+        assert_eq!(ansi_width("\x1B[38;2;1;2;3mabc\x1B]0;title\x07\x1B[0m"), 3);
+    }
+
+    #[test]
+    fn test_ansi_width_unicode() {
+        assert_eq!(ansi_width("日本語"), 6);
+        assert_eq!(ansi_width("e\u{301}"), 1);
+        assert_eq!(ansi_width("\u{1F600}"), 2);
+        assert_eq!(ansi_width("ｈｅｌｌｏ"), 10);
+    }
+}